@@ -13,7 +13,19 @@ pub struct Collection {
     pub order: SortOrder,
     pub rss: Option<RelPath>,
     pub jsonfeed: Option<RelPath>,
+    pub atom: Option<RelPath>,
+    /// Most documents in each feed, keeping the newest
+    pub feed_limit: Option<usize>,
+    /// Put the whole rendered document in RSS items' `content:encoded`
+    pub rss_full_content: bool,
+    /// Feeds of the documents under each tag
+    pub tag_feeds: TermFeeds,
+    /// Feeds of the documents under each category, subcategories included
+    pub category_feeds: TermFeeds,
+    /// Podcast metadata for the RSS feed
+    pub podcast: Option<Podcast>,
     pub publish_date_in_filename: bool,
+    pub related: Related,
     pub default: Frontmatter,
 }
 
@@ -27,7 +39,14 @@ impl From<PostCollection> for Collection {
             order,
             rss,
             jsonfeed,
+            atom,
+            feed_limit,
+            rss_full_content,
+            tag_feeds,
+            category_feeds,
+            podcast,
             publish_date_in_filename,
+            related,
             default,
         } = other;
         Self {
@@ -38,7 +57,14 @@ impl From<PostCollection> for Collection {
             order,
             rss,
             jsonfeed,
+            atom,
+            feed_limit,
+            rss_full_content,
+            tag_feeds,
+            category_feeds,
+            podcast,
             publish_date_in_filename,
+            related,
             default,
         }
     }
@@ -83,7 +109,19 @@ pub struct PostCollection {
     pub order: SortOrder,
     pub rss: Option<RelPath>,
     pub jsonfeed: Option<RelPath>,
+    pub atom: Option<RelPath>,
+    /// Most documents in each feed, keeping the newest
+    pub feed_limit: Option<usize>,
+    /// Put the whole rendered document in RSS items' `content:encoded`
+    pub rss_full_content: bool,
+    /// Feeds of the documents under each tag
+    pub tag_feeds: TermFeeds,
+    /// Feeds of the documents under each category, subcategories included
+    pub category_feeds: TermFeeds,
+    /// Podcast metadata for the RSS feed
+    pub podcast: Option<Podcast>,
     pub publish_date_in_filename: bool,
+    pub related: Related,
     pub default: Frontmatter,
 }
 
@@ -97,12 +135,54 @@ impl Default for PostCollection {
             order: Default::default(),
             rss: Default::default(),
             jsonfeed: Default::default(),
+            atom: Default::default(),
+            feed_limit: Default::default(),
+            rss_full_content: Default::default(),
+            tag_feeds: Default::default(),
+            category_feeds: Default::default(),
+            podcast: Default::default(),
             publish_date_in_filename: true,
+            related: Default::default(),
             default: Default::default(),
         }
     }
 }
 
+/// Where the feeds of each term are written, templated with the term's slug as `tag` or `category`
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct TermFeeds {
+    pub rss: Option<liquid_core::model::KString>,
+    pub jsonfeed: Option<liquid_core::model::KString>,
+}
+
+/// How `page.related` ranks the other documents of a collection
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct Related {
+    /// Most documents to list, `0` disabling `page.related`
+    pub limit: usize,
+    /// Frontmatter lists to compare, with what each shared entry adds to the score
+    pub fields: std::collections::BTreeMap<liquid_core::model::KString, u32>,
+}
+
+impl Default for Related {
+    fn default() -> Self {
+        Self {
+            limit: 5,
+            fields: [("tags".into(), 1), ("categories".into(), 1)]
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "preview_unstable", serde(rename_all = "snake_case"))]
 #[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
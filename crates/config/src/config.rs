@@ -17,12 +17,26 @@ pub struct Config {
     #[serde(skip)]
     pub abs_dest: Option<path::PathBuf>,
     pub include_drafts: bool,
+    /// Include documents whose `published_date` is after the build time
+    pub include_future: bool,
+    /// Render documents past their `expiry_date`, flagged with `is_expired`, instead of dropping
+    /// them
+    pub include_expired: bool,
     pub default: Frontmatter,
     pub pages: PageCollection,
     pub posts: PostCollection,
+    pub collections: std::collections::BTreeMap<liquid_core::model::KString, Collection>,
     pub site: Site,
+    /// Term pages to generate from `posts`, keyed by the frontmatter list they index
+    pub taxonomies: std::collections::BTreeMap<liquid_core::model::KString, Taxonomy>,
+    /// Index of the site's text, written once everything is rendered
+    pub search: Option<Search>,
+    /// Reading speed behind `page.reading_time`, each CJK character counting as a word
+    pub words_per_minute: u32,
+    pub last_modified: LastModified,
     pub template_extensions: Vec<liquid_core::model::KString>,
     pub ignore: Vec<liquid_core::model::KString>,
+    pub keep_files: Vec<liquid_core::model::KString>,
     pub syntax_highlight: SyntaxHighlight,
     #[serde(skip)]
     pub layouts_dir: &'static str,
@@ -40,12 +54,20 @@ impl Default for Config {
             destination: "./_site".try_into().unwrap(),
             abs_dest: Default::default(),
             include_drafts: false,
+            include_future: false,
+            include_expired: false,
             default: Default::default(),
             pages: Default::default(),
             posts: Default::default(),
+            collections: Default::default(),
             site: Default::default(),
+            taxonomies: Default::default(),
+            search: Default::default(),
+            words_per_minute: 200,
+            last_modified: Default::default(),
             template_extensions: vec!["md".into(), "wiki".into(), "liquid".into()],
             ignore: Default::default(),
+            keep_files: vec![".git".into(), "CNAME".into()],
             syntax_highlight: SyntaxHighlight::default(),
             layouts_dir: "_layouts",
             includes_dir: "_includes",
@@ -27,6 +27,34 @@ pub struct Frontmatter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub published_date: Option<DateTime>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry_date: Option<DateTime>,
+    /// When the content last changed meaningfully, for `page.last_modified`
+    #[serde(skip_serializing_if = "Option::is_none", alias = "updated")]
+    pub updated_date: Option<DateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<liquid_core::model::KString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<liquid_core::model::KString>,
+    /// Title of the multi-part series this document belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series: Option<liquid_core::model::KString>,
+    /// Old permalinks that redirect here
+    #[serde(skip_serializing_if = "Option::is_none", alias = "redirect_from")]
+    pub aliases: Option<Vec<liquid_core::model::KString>>,
+    /// Whether the document is listed in its collection's feeds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed: Option<bool>,
+    /// Media for RSS enclosures, like a podcast episode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enclosure: Option<Enclosure>,
+    /// Whether the document, and its paginated pages, are listed in the sitemap
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sitemap: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sitemap_priority: Option<SitemapPriority>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sitemap_changefreq: Option<ChangeFreq>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub format: Option<SourceFormat>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub templated: Option<bool>,
@@ -103,6 +131,17 @@ impl Frontmatter {
             tags,
             excerpt_separator,
             published_date,
+            expiry_date,
+            updated_date,
+            lang,
+            author,
+            series,
+            aliases,
+            feed,
+            enclosure,
+            sitemap,
+            sitemap_priority,
+            sitemap_changefreq,
             format,
             templated,
             layout,
@@ -122,6 +161,17 @@ impl Frontmatter {
             tags: tags.or_else(|| other.tags.clone()),
             excerpt_separator: excerpt_separator.or_else(|| other.excerpt_separator.clone()),
             published_date: published_date.or(other.published_date),
+            expiry_date: expiry_date.or(other.expiry_date),
+            updated_date: updated_date.or(other.updated_date),
+            lang: lang.or_else(|| other.lang.clone()),
+            author: author.or_else(|| other.author.clone()),
+            series: series.or_else(|| other.series.clone()),
+            aliases: aliases.or_else(|| other.aliases.clone()),
+            feed: feed.or(other.feed),
+            enclosure: enclosure.or_else(|| other.enclosure.clone()),
+            sitemap: sitemap.or(other.sitemap),
+            sitemap_priority: sitemap_priority.or(other.sitemap_priority),
+            sitemap_changefreq: sitemap_changefreq.or(other.sitemap_changefreq),
             format: format.or(other.format),
             templated: templated.or(other.templated),
             layout: layout.or_else(|| other.layout.clone()),
@@ -355,4 +405,10 @@ mod test {
         };
         assert_eq!(&front.to_string(), "permalink: foo");
     }
+
+    #[test]
+    fn redirect_from_alias() {
+        let front: Frontmatter = serde_yaml::from_str("redirect_from: [/old.html]").unwrap();
+        assert_eq!(front.aliases, Some(vec!["/old.html".into()]));
+    }
 }
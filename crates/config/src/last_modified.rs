@@ -0,0 +1,35 @@
+/// Where `page.last_modified` comes from
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct LastModified {
+    /// Tried in order, the first to know a date winning
+    pub sources: Vec<LastModifiedSource>,
+}
+
+impl Default for LastModified {
+    fn default() -> Self {
+        Self {
+            sources: vec![LastModifiedSource::Frontmatter],
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "preview_unstable", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub enum LastModifiedSource {
+    /// `updated_date`, falling back to `published_date`
+    Frontmatter,
+    /// Date of the last commit touching the source file
+    Git,
+    /// Modification time of the source file
+    Mtime,
+    #[cfg(not(feature = "unstable"))]
+    #[doc(hidden)]
+    #[serde(other)]
+    Unknown,
+}
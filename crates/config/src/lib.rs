@@ -8,8 +8,13 @@ mod collection;
 mod config;
 mod document;
 mod frontmatter;
+mod last_modified;
 mod pagination;
+mod podcast;
+mod search;
 mod site;
+mod sitemap;
+mod taxonomy;
 
 pub mod path;
 
@@ -18,8 +23,13 @@ pub use self::collection::*;
 pub use self::config::*;
 pub use self::document::*;
 pub use self::frontmatter::*;
+pub use self::last_modified::*;
 pub use self::pagination::*;
+pub use self::podcast::*;
+pub use self::search::*;
 pub use self::site::*;
+pub use self::sitemap::*;
+pub use self::taxonomy::*;
 pub use liquid_core::model::DateTime;
 pub use path::RelPath;
 
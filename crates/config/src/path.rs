@@ -186,6 +186,30 @@ pub fn split_ext(name: &str) -> (&str, Option<&str>) {
         .unwrap_or_else(|| (name, None))
 }
 
+/// Whether a file name extension looks like a language code, like `de` or `pt-BR`
+pub fn is_lang_code(ext: &str) -> bool {
+    static LANG_CODE: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+        regex::Regex::new(r"^[a-z]{2}(-[a-zA-Z0-9]{2,4})?$").unwrap()
+    });
+    LANG_CODE.is_match(ext)
+}
+
+/// The language code of a file name like `post.de.md`, with the name of the file it translates
+///
+/// Whether the code is really a language is up to the caller: `app.js.liquid` looks the same.
+pub fn split_lang(name: &str) -> Option<(String, &str)> {
+    let (rest, ext) = split_ext(name);
+    ext?;
+    #[cfg(feature = "preview_unstable")]
+    let rest = match ext {
+        Some("liquid") => split_ext(rest).0,
+        _ => rest,
+    };
+    let (stem, lang) = split_ext(rest);
+    let lang = lang.filter(|lang| is_lang_code(lang))?;
+    Some((format!("{}{}", stem, &name[rest.len()..]), lang))
+}
+
 static DATE_PREFIX_REF: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
     regex::Regex::new(r"^(\d{4})-(\d{1,2})-(\d{1,2})[- ](.*)$").unwrap()
 });
@@ -222,6 +246,44 @@ pub fn parse_file_stem(stem: &str) -> (Option<crate::DateTime>, liquid_core::mod
     parts.unwrap_or_else(|| (None, liquid_core::model::KString::from_ref(stem)))
 }
 
+#[cfg(test)]
+mod test_lang {
+    use super::*;
+
+    #[test]
+    fn is_lang_code_language() {
+        assert!(is_lang_code("de"));
+    }
+
+    #[test]
+    fn is_lang_code_region() {
+        assert!(is_lang_code("pt-BR"));
+        assert!(is_lang_code("zh-Hans"));
+    }
+
+    #[test]
+    fn is_lang_code_other_ext() {
+        assert!(!is_lang_code("html"));
+        assert!(!is_lang_code("min"));
+        assert!(!is_lang_code("EN"));
+    }
+
+    #[test]
+    fn split_lang_translation() {
+        assert_eq!(
+            split_lang("2017-03-05-hello.de.md"),
+            Some(("2017-03-05-hello.md".to_owned(), "de"))
+        );
+    }
+
+    #[test]
+    fn split_lang_none() {
+        assert_eq!(split_lang("hello.md"), None);
+        assert_eq!(split_lang("hello"), None);
+        assert_eq!(split_lang("jquery.min.js"), None);
+    }
+}
+
 #[cfg(test)]
 mod test_stem {
     use super::*;
@@ -0,0 +1,54 @@
+/// Media attached to a document, like the audio of a podcast episode
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct Enclosure {
+    /// Site-relative path, like `/episodes/1.mp3`, or absolute URL
+    pub url: liquid_core::model::KString,
+    /// Size in bytes, read from the file when it is part of the site
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<u64>,
+    /// Guessed from the extension of `url` when missing
+    #[serde(skip_serializing_if = "Option::is_none", alias = "type")]
+    pub mime_type: Option<liquid_core::model::KString>,
+    /// Playing time, as `HH:MM:SS`, `MM:SS` or seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<liquid_core::model::KString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explicit: Option<bool>,
+    /// Episode artwork, overriding the podcast's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<liquid_core::model::KString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub episode: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub season: Option<u32>,
+}
+
+/// Show-level metadata turning a collection's RSS feed into a podcast feed
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct Podcast {
+    pub author: Option<liquid_core::model::KString>,
+    /// Show artwork, a site-relative path or absolute URL
+    pub image: Option<liquid_core::model::KString>,
+    /// Apple Podcasts categories, subcategories written as `Category/Subcategory`
+    pub categories: Vec<liquid_core::model::KString>,
+    pub explicit: bool,
+    pub owner: Option<PodcastOwner>,
+}
+
+/// Who podcast directories contact about the show
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct PodcastOwner {
+    pub name: Option<liquid_core::model::KString>,
+    pub email: Option<liquid_core::model::KString>,
+}
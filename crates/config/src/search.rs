@@ -0,0 +1,53 @@
+use super::*;
+
+/// Index of the site's text, for searching it without a server
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct Search {
+    /// Where the index is written, relative to the destination
+    pub path: RelPath,
+    /// Slugs of the collections to index, `pages` included
+    pub collections: Vec<liquid_core::model::KString>,
+    /// Attributes of each document to put in the index
+    pub fields: Vec<liquid_core::model::KString>,
+    /// Most characters of `content` to keep per document, `0` keeping all of it
+    pub content_length: usize,
+    pub format: SearchFormat,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self {
+            path: RelPath::from_unchecked("search.json"),
+            collections: vec!["posts".into()],
+            fields: vec![
+                "title".into(),
+                "permalink".into(),
+                "tags".into(),
+                "excerpt".into(),
+                "content".into(),
+            ],
+            content_length: 0,
+            format: Default::default(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "preview_unstable", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub enum SearchFormat {
+    /// Array of documents, for the client to index when loading
+    #[default]
+    Documents,
+    /// Prebuilt index for elasticlunr's `Index.load`
+    Elasticlunr,
+    #[cfg(not(feature = "unstable"))]
+    #[doc(hidden)]
+    #[serde(other)]
+    Unknown,
+}
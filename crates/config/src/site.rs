@@ -7,7 +7,17 @@ pub struct Site {
     pub title: Option<liquid_core::model::KString>,
     pub description: Option<liquid_core::model::KString>,
     pub base_url: Option<liquid_core::model::KString>,
+    /// Language of documents that don't specify one
+    pub lang: Option<liquid_core::model::KString>,
+    /// Languages, besides `lang`, recognized in file names like `post.de.md`
+    pub languages: Vec<liquid_core::model::KString>,
+    /// Author of documents that don't name one
+    pub author: Option<liquid_core::model::KString>,
     pub sitemap: Option<crate::RelPath>,
+    /// File listing every alias, for servers that can redirect on their own
+    pub redirects: Option<Redirects>,
+    /// `robots.txt`, telling crawlers what to index
+    pub robots: Option<Robots>,
     pub data: Option<liquid_core::Object>,
     #[serde(skip)]
     pub data_dir: &'static str,
@@ -19,9 +29,82 @@ impl Default for Site {
             title: Default::default(),
             description: Default::default(),
             base_url: Default::default(),
+            lang: Default::default(),
+            languages: Default::default(),
+            author: Default::default(),
             sitemap: Default::default(),
+            redirects: Default::default(),
+            robots: Default::default(),
             data: Default::default(),
             data_dir: "_data",
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct Redirects {
+    pub path: crate::RelPath,
+    #[serde(default)]
+    pub format: RedirectsFormat,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "preview_unstable", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub enum RedirectsFormat {
+    /// `_redirects` file, as read by Netlify and Cloudflare Pages
+    #[default]
+    Netlify,
+    /// Entries of an nginx `map` from the old to the new path
+    Nginx,
+    #[cfg(not(feature = "unstable"))]
+    #[doc(hidden)]
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct Robots {
+    pub path: crate::RelPath,
+    /// Groups of rules, crawlers being allowed everywhere when empty
+    pub rules: Vec<RobotsRule>,
+}
+
+impl Default for Robots {
+    fn default() -> Self {
+        Self {
+            path: crate::RelPath::from_unchecked("robots.txt"),
+            rules: Default::default(),
+        }
+    }
+}
+
+/// Paths the crawlers matching `user_agent` may or may not visit
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct RobotsRule {
+    pub user_agent: liquid_core::model::KString,
+    pub allow: Vec<liquid_core::model::KString>,
+    pub disallow: Vec<liquid_core::model::KString>,
+}
+
+impl Default for RobotsRule {
+    fn default() -> Self {
+        Self {
+            user_agent: liquid_core::model::KString::from_static("*"),
+            allow: Default::default(),
+            disallow: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+
+/// How often a page is expected to change, as a hint for crawlers
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "preview_unstable", serde(rename_all = "snake_case"))]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub enum ChangeFreq {
+    Always,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+    Never,
+    #[cfg(not(feature = "unstable"))]
+    #[doc(hidden)]
+    #[serde(other)]
+    Unknown,
+}
+
+impl ChangeFreq {
+    /// The value of `<changefreq>`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+            Self::Never => "never",
+            #[cfg(not(feature = "unstable"))]
+            Self::Unknown => "always",
+        }
+    }
+}
+
+/// How important a page is relative to the rest of the site, from `0.0` to `1.0`
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize)]
+#[repr(transparent)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct SitemapPriority(f64);
+
+impl SitemapPriority {
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+// `TryFrom` rejects `NaN`
+impl Eq for SitemapPriority {}
+
+impl TryFrom<f64> for SitemapPriority {
+    type Error = &'static str;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if (0.0..=1.0).contains(&value) {
+            Ok(Self(value))
+        } else {
+            Err("Sitemap priorities must be between 0.0 and 1.0")
+        }
+    }
+}
+
+impl From<SitemapPriority> for f64 {
+    fn from(value: SitemapPriority) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for SitemapPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
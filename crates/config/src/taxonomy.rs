@@ -0,0 +1,19 @@
+use super::*;
+
+/// Pages generated for the terms of a frontmatter list, like `tags`
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "unstable", serde(deny_unknown_fields))]
+#[cfg_attr(not(feature = "unstable"), non_exhaustive)]
+pub struct Taxonomy {
+    pub title: Option<liquid_core::model::KString>,
+    /// Where the list of terms is written, each term's pages going underneath it
+    pub permalink: Option<ExplicitPermalink>,
+    /// Layout of the list of terms
+    pub layout: Option<liquid_core::model::KString>,
+    /// Layout of each term's pages, defaulting to `layout`
+    pub term_layout: Option<liquid_core::model::KString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+}
@@ -18,6 +18,22 @@ pub(crate) struct ConfigArgs {
     /// Ignore drafts.
     #[arg(long, conflicts_with = "drafts")]
     no_drafts: bool,
+
+    /// Include documents dated in the future.
+    #[arg(long)]
+    future: bool,
+
+    /// Hold back documents dated in the future.
+    #[arg(long, conflicts_with = "future")]
+    no_future: bool,
+
+    /// Include documents past their expiry date.
+    #[arg(long)]
+    expired: bool,
+
+    /// Leave out documents past their expiry date.
+    #[arg(long, conflicts_with = "expired")]
+    no_expired: bool,
 }
 
 impl ConfigArgs {
@@ -36,6 +52,12 @@ impl ConfigArgs {
         if let Some(drafts) = self.drafts() {
             config.include_drafts = drafts;
         }
+        if let Some(future) = resolve_bool_arg(self.future, self.no_future) {
+            config.include_future = future;
+        }
+        if let Some(expired) = resolve_bool_arg(self.expired, self.no_expired) {
+            config.include_expired = expired;
+        }
 
         Ok(config)
     }
@@ -11,6 +11,22 @@ pub(crate) struct BuildArgs {
     #[arg(short, long, value_name = "DIR", help_heading = "Config")]
     destination: Option<std::path::PathBuf>,
 
+    /// Number of documents to render in parallel [default: number of CPUs]
+    #[arg(short, long, value_name = "NUM")]
+    jobs: Option<usize>,
+
+    /// Only re-render what changed since the last incremental build
+    #[arg(long)]
+    incremental: bool,
+
+    /// Report stale files in the destination instead of removing them
+    #[arg(long)]
+    prune_dry_run: bool,
+
+    /// Check for broken internal links once built
+    #[arg(long)]
+    check_links: bool,
+
     #[command(flatten, next_help_heading = "Config")]
     pub(crate) config: args::ConfigArgs,
 }
@@ -27,11 +43,21 @@ impl BuildArgs {
             })
             .transpose()?;
 
-        let config = cobalt::cobalt_model::Config::from_config(config)?;
+        let mut config = cobalt::cobalt_model::Config::from_config(config)?;
+        config.jobs = self.jobs;
+        config.prune_dry_run = self.prune_dry_run;
+        config.prune = true;
+        if self.incremental {
+            config.cache_dir = Some(config.source.join(cobalt::cobalt_model::CACHE_DIR));
+        }
 
-        build(config)?;
+        build(config.clone())?;
         log::info!("Build successful");
 
+        if self.check_links {
+            crate::check::check_links(config)?;
+        }
+
         Ok(())
     }
 }
@@ -64,6 +90,12 @@ impl CleanArgs {
 }
 
 pub(crate) fn clean(config: &cobalt::Config) -> Result<()> {
+    let cache_dir = config.source.join(cobalt::cobalt_model::CACHE_DIR);
+    if cache_dir.is_dir() {
+        fs::remove_dir_all(&cache_dir)?;
+        log::info!("directory `{}` removed", cache_dir.display());
+    }
+
     let cwd = env::current_dir().unwrap_or_default();
     let destdir = dunce::canonicalize(&config.destination);
     let destdir = match destdir {
@@ -0,0 +1,57 @@
+use anyhow::Context as _;
+
+use crate::args;
+use crate::error::Result;
+
+/// Check the generated site for problems
+#[derive(Clone, Debug, PartialEq, Eq, clap::Subcommand)]
+pub(crate) enum CheckCommands {
+    /// Report internal links that don't resolve
+    Links {
+        /// Site destination folder [default: ./_site]
+        #[arg(short, long, value_name = "DIR", help_heading = "Config")]
+        destination: Option<std::path::PathBuf>,
+
+        #[command(flatten, next_help_heading = "Config")]
+        config: args::ConfigArgs,
+    },
+}
+
+impl CheckCommands {
+    pub(crate) fn run(&self) -> Result<()> {
+        match self {
+            Self::Links {
+                destination,
+                config,
+            } => {
+                let mut config = config.load_config()?;
+                config.abs_dest = destination
+                    .as_deref()
+                    .map(|d| {
+                        dunce::canonicalize(d).with_context(|| {
+                            anyhow::format_err!("Could not find destination `{}`", d.display())
+                        })
+                    })
+                    .transpose()?;
+                let config = cobalt::cobalt_model::Config::from_config(config)?;
+                check_links(config)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn check_links(config: cobalt::Config) -> Result<()> {
+    log::info!("Checking links in `{}`", config.destination.display());
+    let broken = cobalt::check_links(config)?;
+    for link in &broken {
+        log::error!("{link}");
+    }
+    if !broken.is_empty() {
+        anyhow::bail!("Found {} broken link(s)", broken.len());
+    }
+    log::info!("No broken links found");
+
+    Ok(())
+}
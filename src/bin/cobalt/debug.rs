@@ -14,6 +14,12 @@ pub(crate) enum DebugCommands {
     #[command(subcommand)]
     Highlight(HighlightCommands),
 
+    /// Print documents held back until their `published_date`
+    Scheduled {
+        #[command(flatten, next_help_heading = "Config")]
+        config: args::ConfigArgs,
+    },
+
     /// Print files associated with a collection
     Files {
         /// Collection name
@@ -58,6 +64,13 @@ impl DebugCommands {
                     println!("{name}");
                 }
             }
+            Self::Scheduled { config } => {
+                let config = config.load_config()?;
+                let config = cobalt::cobalt_model::Config::from_config(config)?;
+                for doc in cobalt::find_scheduled(config)? {
+                    println!("{} {}", doc.published_date, doc.source);
+                }
+            }
             Self::Files { collection, config } => {
                 let config = config.load_config()?;
                 let config = cobalt::cobalt_model::Config::from_config(config)?;
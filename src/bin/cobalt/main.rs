@@ -2,6 +2,7 @@
 
 mod args;
 mod build;
+mod check;
 mod debug;
 mod error;
 mod new;
@@ -43,6 +44,8 @@ enum Command {
     #[cfg(feature = "serve")]
     Serve(serve::ServeArgs),
     #[command(subcommand)]
+    Check(check::CheckCommands),
+    #[command(subcommand)]
     Debug(debug::DebugCommands),
 }
 
@@ -64,6 +67,7 @@ impl Cli {
             Command::Clean(cmd) => cmd.run(),
             #[cfg(feature = "serve")]
             Command::Serve(cmd) => cmd.run(),
+            Command::Check(cmd) => cmd.run(),
             Command::Debug(cmd) => cmd.run(),
         }
     }
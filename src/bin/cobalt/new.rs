@@ -259,7 +259,7 @@ pub(crate) fn create_new_document(
         match cobalt::classify_path(
             &interim_path.rel_path,
             &config.pages,
-            &config.posts,
+            config.document_collections(),
             &config.page_extensions,
         ) {
             Some((slug, _)) => slug,
@@ -380,12 +380,12 @@ pub(crate) fn rename_document(
         match cobalt::classify_path(
             &target.rel_path,
             &config.pages,
-            &config.posts,
+            config.document_collections(),
             &config.page_extensions,
         ) {
-            Some((slug, _)) if config.pages.slug == slug => &config.pages,
-            Some((slug, _)) if config.posts.slug == slug => &config.posts,
-            Some((slug, _)) => unreachable!("Unknown collection: {}", slug),
+            Some((slug, _)) => config
+                .collection(slug)
+                .unwrap_or_else(|| unreachable!("Unknown collection: {}", slug)),
             None => anyhow::bail!("Target file is an asset: {}", target.rel_path),
         }
     } else {
@@ -405,7 +405,9 @@ pub(crate) fn rename_document(
     cobalt_model::files::write_document_file(doc, &target.abs_path)?;
 
     if !full_front.is_draft {
-        log::warn!("Renaming a published page might invalidate links");
+        log::warn!(
+            "Renaming a published page might invalidate links; add its old permalink to `aliases`"
+        );
     }
     fs::remove_file(source)?;
 
@@ -445,16 +447,20 @@ fn prepend_date_to_filename(
     Ok(())
 }
 
-fn move_from_drafts_to_posts(
+fn move_from_drafts_to_collection(
     config: &cobalt_model::Config,
     file: &path::Path,
 ) -> Result<path::PathBuf> {
-    if let Some(drafts_dir) = config.posts.drafts_dir.as_ref() {
+    for collection in config.document_collections() {
+        let Some(drafts_dir) = collection.drafts_dir.as_ref() else {
+            continue;
+        };
         let drafts_root = drafts_dir.to_path(&config.source);
         if let Ok(relpath) = file.strip_prefix(drafts_root) {
-            let target = config.posts.dir.to_path(&config.source).join(relpath);
+            let target = collection.dir.to_path(&config.source).join(relpath);
             log::trace!(
-                "post is in `drafts_dir`; moving it to `posts` directory: {}",
+                "document is in `drafts_dir`; moving it to `{}` directory: {}",
+                collection.slug,
                 target.display()
             );
             if let Some(parent) = target.parent() {
@@ -483,7 +489,7 @@ pub(crate) fn publish_document(config: &cobalt_model::Config, file: &path::Path)
     let doc = doc.to_string();
     cobalt_model::files::write_document_file(doc, file)?;
 
-    let file = move_from_drafts_to_posts(config, file)?;
+    let file = move_from_drafts_to_collection(config, file)?;
     let file = cobalt_core::SourcePath::from_root(&config.source, &file).ok_or_else(|| {
         anyhow::format_err!(
             "New file {} not in project directory ({})",
@@ -498,12 +504,12 @@ pub(crate) fn publish_document(config: &cobalt_model::Config, file: &path::Path)
         match cobalt::classify_path(
             &file.rel_path,
             &config.pages,
-            &config.posts,
+            config.document_collections(),
             &config.page_extensions,
         ) {
-            Some((slug, _)) if config.pages.slug == slug => &config.pages,
-            Some((slug, _)) if config.posts.slug == slug => &config.posts,
-            Some((slug, _)) => unreachable!("Unknown collection: {}", slug),
+            Some((slug, _)) => config
+                .collection(slug)
+                .unwrap_or_else(|| unreachable!("Unknown collection: {}", slug)),
             None => anyhow::bail!("Target file is an asset: {}", file.rel_path),
         }
     } else {
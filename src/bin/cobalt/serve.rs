@@ -30,6 +30,10 @@ pub(crate) struct ServeArgs {
     #[arg(long)]
     pub(crate) no_watch: bool,
 
+    /// Number of documents to render in parallel [default: number of CPUs]
+    #[arg(short, long, value_name = "NUM")]
+    pub(crate) jobs: Option<usize>,
+
     #[command(flatten, next_help_heading = "Config")]
     pub(crate) config: args::ConfigArgs,
 }
@@ -37,6 +41,8 @@ pub(crate) struct ServeArgs {
 impl ServeArgs {
     pub(crate) fn run(&self) -> Result<()> {
         let dest = tempfile::tempdir()?;
+        // Kept outside of the source so writing it doesn't trigger another rebuild
+        let cache = tempfile::tempdir()?;
 
         let mut server = file_serve::ServerBuilder::new(dest.path());
         server.hostname(&self.host);
@@ -55,6 +61,9 @@ impl ServeArgs {
             dest.path().display()
         );
         config.destination = dest.path().to_owned();
+        config.jobs = self.jobs;
+        config.cache_dir = Some(cache.path().to_owned());
+        config.prune = true;
         build::build(config.clone())?;
 
         if self.open {
@@ -66,6 +75,7 @@ impl ServeArgs {
             serve(&server)?;
 
             dest.close()?;
+            cache.close()?;
         } else {
             log::info!("Watching {} for changes", &config.source.display());
             thread::spawn(move || {
@@ -1,208 +1,1061 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fs;
 use std::io::Write;
 use std::path;
+use std::sync::Mutex;
 
 use anyhow::Context as _;
 use jsonfeed::Feed;
 use log::debug;
+use log::info;
 use log::trace;
 use log::warn;
-use sitemap::writer::SiteMapWriter;
+use rayon::prelude::*;
 
 use crate::cobalt_model;
 use crate::cobalt_model::Collection;
 use crate::cobalt_model::files;
 use crate::cobalt_model::permalink;
 use crate::cobalt_model::{Config, Minify, SortOrder};
-use crate::document::{Document, RenderContext};
+use crate::document::{Document, RenderContext, permalink_attributes, to_fixed_date_time};
 use crate::error::Result;
+use crate::globals::Globals;
+use crate::incremental;
+use crate::last_modified;
+use crate::layout;
+use crate::layout::Layout;
 use crate::pagination;
+use crate::podcast;
+use crate::redirects;
+use crate::related;
+use crate::robots;
+use crate::search;
+use crate::series;
+use crate::sitemap;
+use crate::term_feeds;
 
 struct Context {
     pub(crate) destination: path::PathBuf,
     pub(crate) source_files: cobalt_core::Source,
     pub(crate) page_extensions: Vec<liquid::model::KString>,
     pub(crate) include_drafts: bool,
+    pub(crate) include_future: bool,
+    pub(crate) include_expired: bool,
     pub(crate) pages: Collection,
     pub(crate) posts: Collection,
+    pub(crate) collections: Vec<Collection>,
+    pub(crate) taxonomies: Vec<cobalt_model::Taxonomy>,
+    pub(crate) search: Option<cobalt_config::Search>,
+    pub(crate) words_per_minute: u32,
+    pub(crate) last_modified: cobalt_config::LastModified,
     pub(crate) site: cobalt_model::Site,
     pub(crate) site_attributes: liquid::Object,
-    pub(crate) layouts: HashMap<String, String>,
+    pub(crate) layouts: HashMap<String, Layout>,
+    /// Permalinks compiled so far in this build
+    pub(crate) permalinks: permalink::Templates,
     pub(crate) liquid: cobalt_model::Liquid,
     pub(crate) markdown: cobalt_model::Markdown,
     pub(crate) assets: cobalt_model::Assets,
     pub(crate) minify: Minify,
+    pub(crate) jobs: Option<usize>,
+    pub(crate) cache: incremental::Cache,
+    pub(crate) keep_files: Vec<liquid::model::KString>,
+    pub(crate) prune_dry_run: bool,
+    pub(crate) prune: bool,
+    pub(crate) outputs: Mutex<HashSet<path::PathBuf>>,
 }
 
 impl Context {
     fn with_config(config: Config) -> Result<Self> {
+        let source_files =
+            cobalt_core::Source::new(&config.source, config.ignore.iter().map(|s| s.as_str()))?;
+        let cache = incremental::Cache::load(&config, &source_files)?;
+
         let Config {
             source,
             destination,
-            ignore,
+            ignore: _,
+            keep_files,
             page_extensions,
             include_drafts,
+            include_future,
+            include_expired,
             pages,
             posts,
+            collections,
             site,
+            taxonomies,
+            search,
+            words_per_minute,
+            last_modified,
             layouts_path,
             liquid,
             markdown,
             syntax: _,
             assets,
             minify,
+            jobs,
+            cache_dir: _,
+            prune_dry_run,
+            prune,
         } = config;
 
-        let source_files = cobalt_core::Source::new(&source, ignore.iter().map(|s| s.as_str()))?;
         let site_attributes = site.load(&source)?;
         let liquid = liquid.build()?;
         let markdown = markdown.build();
         let assets = assets.build()?;
 
-        let layouts = find_layouts(&layouts_path)?;
-        let layouts = parse_layouts(&layouts);
+        let layouts = layout::find_layouts(&layouts_path)?;
+        let layouts = layout::parse_layouts(&layouts, &liquid)?;
 
         let context = Context {
             destination,
             source_files,
             page_extensions,
             include_drafts,
+            include_future,
+            include_expired,
             pages,
             posts,
+            collections,
+            taxonomies,
+            search,
+            words_per_minute,
+            last_modified,
             site,
             site_attributes,
             layouts,
+            permalinks: Default::default(),
             liquid,
             markdown,
             assets,
             minify,
+            jobs,
+            cache,
+            keep_files,
+            prune_dry_run,
+            prune,
+            outputs: Default::default(),
         };
         Ok(context)
     }
+
+    /// Remember that `path` was produced by this build so it isn't pruned
+    fn record_output(&self, path: path::PathBuf) {
+        self.outputs
+            .lock()
+            .expect("only poisoned if a render panicked")
+            .insert(path);
+    }
+
+    fn record_outputs(&self, entry: &incremental::Entry) {
+        for output in entry.outputs() {
+            self.record_output(output.to_path(&self.destination));
+        }
+    }
+
+    /// Collections of documents, beyond `pages`, in classification order.
+    fn document_collections(&self) -> impl Iterator<Item = &Collection> {
+        std::iter::once(&self.posts).chain(self.collections.iter())
+    }
 }
 
 /// The primary build function that transforms a directory into a site
 pub fn build(config: Config) -> Result<()> {
     let context = Context::with_config(config)?;
 
-    let mut post_paths = Vec::new();
-    let mut post_draft_paths = Vec::new();
+    // Documents are rendered independently of each other, so spread them across a pool
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(context.jobs.unwrap_or(0))
+        .build()?;
+    pool.install(|| build_with_context(&context))
+}
+
+fn build_with_context(context: &Context) -> Result<()> {
+    let Sources {
+        collections,
+        mut collection_docs,
+        documents,
+        asset_paths,
+        scheduled: _,
+    } = parse_sources(context)?;
+
+    // listings show the same `series` as the document itself, so it's set before collecting them
+    for docs in &mut collection_docs {
+        add_series(docs);
+    }
+
+    // collect all collections' attributes so documents can reference each other while rendering
+    let simple_collections_data: Vec<_> = collection_docs
+        .iter()
+        .map(|docs| collection_data(docs))
+        .collect();
+    let posts_data = posts_data(context, &collections, &simple_collections_data);
+    let outputs = output_sources(
+        context,
+        &collections,
+        &collection_docs,
+        &documents,
+        posts_data,
+        &asset_paths,
+    )?;
+    check_output_collisions(&outputs)?;
+
+    let global_collection = generate_collections_var(&collections, &simple_collections_data);
+    let tracker = context.cache.tracker(collection_docs.iter().flatten())?;
+    for (collection, docs) in collections.iter().zip(&mut collection_docs) {
+        generate_posts(docs, collection, context, &global_collection, &tracker)?;
+    }
+
+    for (collection, docs) in collections.iter().zip(&collection_docs) {
+        // check if we should create an RSS file and create it!
+        if let Some(ref path) = collection.rss {
+            for (path, docs) in localized_feeds(context, path, docs) {
+                let path = path.to_path(&context.destination);
+                create_rss(
+                    &path,
+                    collection,
+                    &collection.title,
+                    &docs,
+                    context.site.base_url.as_deref(),
+                )?;
+                context.record_output(path);
+            }
+        }
+        // check if we should create an jsonfeed file and create it!
+        if let Some(ref path) = collection.jsonfeed {
+            for (path, docs) in localized_feeds(context, path, docs) {
+                let path = path.to_path(&context.destination);
+                create_jsonfeed(
+                    &path,
+                    collection,
+                    &collection.title,
+                    &docs,
+                    context.site.base_url.as_deref(),
+                )?;
+                context.record_output(path);
+            }
+        }
+        if let Some(ref path) = collection.atom {
+            for (rel_path, docs) in localized_feeds(context, path, docs) {
+                let path = rel_path.to_path(&context.destination);
+                create_atom(&path, &rel_path, collection, &docs, &context.site)?;
+                context.record_output(path);
+            }
+        }
+        for feed in term_feeds::term_feeds(collection, docs, &context.permalinks)? {
+            let title = format!("{}: {}", collection.title, feed.term);
+            if let Some(path) = feed.rss {
+                let path = path.to_path(&context.destination);
+                let base_url = context.site.base_url.as_deref();
+                create_rss(&path, collection, &title, &feed.documents, base_url)?;
+                context.record_output(path);
+            }
+            if let Some(path) = feed.jsonfeed {
+                let path = path.to_path(&context.destination);
+                let base_url = context.site.base_url.as_deref();
+                create_jsonfeed(&path, collection, &title, &feed.documents, base_url)?;
+                context.record_output(path);
+            }
+        }
+    }
+    if let Some(ref path) = context.site.sitemap {
+        create_sitemap(context, path, &collection_docs, &documents, posts_data)?;
+    }
+    if let Some(config) = context.site.robots.as_ref() {
+        create_robots(context, config)?;
+    }
+    let redirects = redirects::collect(collection_docs.iter().flatten().chain(&documents));
+    create_redirects(context, &redirects)?;
+
+    let mut search = context
+        .search
+        .as_ref()
+        .map(|config| search::IndexBuilder::new(config, context.site.base_url.as_deref()));
+    if let Some(search) = search.as_mut() {
+        for (collection, docs) in collections.iter().zip(&collection_docs) {
+            search.add_collection(&collection.slug, docs);
+        }
+    }
+
+    let documents = generate_pages(&collections, collection_docs, documents, context, &tracker)?;
+
+    if let (Some(mut search), Some(config)) = (search, context.search.as_ref()) {
+        search.add_collection(&context.pages.slug, &documents);
+        let path = config.path.to_path(&context.destination);
+        debug!("Creating search index at {}", path.display());
+        files::write_document_file(search.build()?, &path)?;
+        context.record_output(path);
+    }
+
+    // copy all remaining files in the source to the destination
+    // compile SASS along the way
+    for asset_path in asset_paths {
+        let source = asset_path.rel_path.as_str();
+        if context.assets.is_standalone(&asset_path.abs_path) {
+            let inputs = tracker.asset_inputs(source);
+            if let Some(entry) = tracker.reuse(source, &inputs) {
+                trace!("Skipping unchanged {}", source);
+                context.record_outputs(&entry);
+                tracker.record(source, entry);
+                continue;
+            }
+            let output = context.assets.process(
+                &asset_path.abs_path,
+                &context.destination,
+                &context.minify,
+            )?;
+            context.record_output(output);
+            tracker.record(
+                source,
+                incremental::Entry::new(inputs, vec![source.to_owned()]),
+            );
+        } else {
+            let output = context.assets.process(
+                &asset_path.abs_path,
+                &context.destination,
+                &context.minify,
+            )?;
+            context.record_output(output);
+        }
+    }
+
+    tracker.save()?;
+    prune_destination(context)?;
+
+    Ok(())
+}
+
+/// Documents and assets found in the source, ready to be rendered
+struct Sources<'c> {
+    collections: Vec<&'c Collection>,
+    collection_docs: Vec<Vec<Document>>,
+    documents: Vec<Document>,
+    asset_paths: Vec<cobalt_core::SourcePath>,
+    /// Documents held back until their `published_date`
+    scheduled: Vec<Document>,
+}
+
+fn parse_sources(context: &Context) -> Result<Sources<'_>> {
+    let collections: Vec<_> = context.document_collections().collect();
+    let mut collection_paths = vec![(Vec::new(), Vec::new()); collections.len()];
     let mut page_paths = Vec::new();
     let mut asset_paths = Vec::new();
     for path in context.source_files.iter() {
         match classify_path(
             &path.rel_path,
             &context.pages,
-            &context.posts,
+            collections.iter().copied(),
             &context.page_extensions,
         ) {
             Some((slug, false)) if context.pages.slug == slug => page_paths.push(path),
             Some((slug, true)) if context.pages.slug == slug => {
                 unreachable!("We don't support draft pages")
             }
-            Some((slug, false)) if context.posts.slug == slug => post_paths.push(path),
-            Some((slug, true)) if context.posts.slug == slug => post_draft_paths.push(path),
-            Some((slug, _)) => unreachable!("Unknown collection: {}", slug),
+            Some((slug, is_draft)) => {
+                let i = collections
+                    .iter()
+                    .position(|c| c.slug == slug)
+                    .unwrap_or_else(|| unreachable!("Unknown collection: {}", slug));
+                let (paths, draft_paths) = &mut collection_paths[i];
+                if is_draft {
+                    draft_paths.push(path);
+                } else {
+                    paths.push(path);
+                }
+            }
             None => asset_paths.push(path),
         }
     }
 
-    let mut posts = parse_pages(&post_paths, &context.posts, context.include_drafts)?;
-    if !post_draft_paths.is_empty() {
-        parse_drafts(&post_draft_paths, &mut posts, &context.posts)?;
+    let mut scheduled = Vec::new();
+    let mut collection_docs = Vec::with_capacity(collections.len());
+    for (collection, (paths, draft_paths)) in collections.iter().zip(collection_paths) {
+        let mut docs = parse_pages(&paths, collection, context)?;
+        if !draft_paths.is_empty() {
+            parse_drafts(&draft_paths, &mut docs, collection, context)?;
+        }
+        hold_back_scheduled(context, &mut docs, &mut scheduled);
+        handle_expired(context, &mut docs);
+        sort_pages(&mut docs, collection)?;
+        collection_docs.push(docs);
     }
 
-    let documents = parse_pages(&page_paths, &context.pages, context.include_drafts)?;
+    let mut documents = parse_pages(&page_paths, &context.pages, context)?;
+    hold_back_scheduled(context, &mut documents, &mut scheduled);
+    handle_expired(context, &mut documents);
+    documents.extend(taxonomy_documents(context)?);
+    for doc in &mut documents {
+        if let Some(pagination) = doc.front.pagination.as_mut() {
+            // only the indexes of `posts` tags and categories have feeds to link to
+            pagination.feeds = match pagination.include {
+                cobalt_model::pagination::Include::Tags
+                    if matches!(pagination.field.as_deref(), None | Some("tags")) =>
+                {
+                    context.posts.tag_feeds.clone()
+                }
+                cobalt_model::pagination::Include::Categories => {
+                    context.posts.category_feeds.clone()
+                }
+                _ => Default::default(),
+            };
+        }
+    }
 
-    sort_pages(&mut posts, &context.posts)?;
-    generate_posts(&mut posts, &context)?;
+    let last_modified = last_modified::Dates::new(
+        &context.last_modified,
+        context.source_files.root(),
+        context.cache.dir(),
+    );
+    for doc in collection_docs
+        .iter_mut()
+        .flatten()
+        .chain(documents.iter_mut())
+    {
+        doc.set_last_modified(last_modified.of(doc));
+        if let Some(enclosure) = doc.front.enclosure.as_mut() {
+            podcast::resolve_enclosure(
+                enclosure,
+                context.source_files.root(),
+                context.site.base_url.as_deref(),
+            )
+            .with_context(|| anyhow::format_err!("Invalid enclosure in `{}`", doc.source_path))?;
+        }
+    }
 
-    // check if we should create an RSS file and create it!
-    if let Some(ref path) = context.posts.rss {
-        let path = path.to_path(&context.destination);
-        create_rss(
-            &path,
-            &context.posts,
-            &posts,
-            context.site.base_url.as_deref(),
-        )?;
+    localize(
+        context,
+        collection_docs
+            .iter_mut()
+            .flatten()
+            .chain(documents.iter_mut()),
+    )?;
+
+    Ok(Sources {
+        collections,
+        collection_docs,
+        documents,
+        asset_paths,
+        scheduled,
+    })
+}
+
+/// The page listing each taxonomy's terms, paginated into the pages of every term
+fn taxonomy_documents(context: &Context) -> Result<Vec<Document>> {
+    context
+        .taxonomies
+        .iter()
+        .map(|taxonomy| {
+            let source =
+                relative_path::RelativePathBuf::from(format!("taxonomies.{}", taxonomy.field));
+            let front = taxonomy.frontmatter().merge(&context.pages.default);
+            let mut doc = Document::new(
+                source.clone(),
+                &source,
+                front,
+                Default::default(),
+                &context.permalinks,
+            )
+            .with_context(|| {
+                anyhow::format_err!("Failed to create taxonomy `{}`", taxonomy.field)
+            })?;
+            let pagination = doc
+                .front
+                .pagination
+                .as_mut()
+                .expect("taxonomies are always paginated");
+            pagination.field = Some(taxonomy.field.clone());
+            pagination.term_layout = Some(taxonomy.term_layout.clone());
+            Ok(doc)
+        })
+        .collect()
+}
+
+/// Prefix the permalinks of documents not in the site's language with their language and link each
+/// document to its translations
+///
+/// Translations are documents with a language whose source files are named alike but for the
+/// language code, like `post.md` and `post.de.md`.
+fn localize<'d>(
+    context: &Context,
+    documents: impl IntoIterator<Item = &'d mut Document>,
+) -> Result<()> {
+    let mut documents: Vec<_> = documents
+        .into_iter()
+        .filter(|doc| doc.front.lang.is_some())
+        .collect();
+    let mut translations: HashMap<_, Vec<_>> = HashMap::new();
+    for (i, doc) in documents.iter_mut().enumerate() {
+        let lang = doc
+            .front
+            .lang
+            .clone()
+            .expect("filtered to documents with a language");
+        if context.site.lang.as_deref() != Some(lang.as_str()) {
+            // the other pages' paths come from this permalink, so pin it under the language
+            let attributes = permalink_attributes(&doc.front, &doc.file_path);
+            if let Some(pagination) = doc.front.pagination.as_mut() {
+                let root = permalink::explode_permalink(
+                    &pagination.front_permalink,
+                    &attributes,
+                    &context.permalinks,
+                )?;
+                pagination.front_permalink = cobalt_config::Permalink::Explicit(
+                    cobalt_config::ExplicitPermalink::from_unchecked(&format!("/{lang}/{root}")),
+                );
+            }
+            doc.url_path = format!("{}/{}", lang, doc.url_path);
+            doc.file_path = permalink::format_url_as_file(&doc.url_path);
+            doc.attributes.insert(
+                "permalink".into(),
+                liquid::model::Value::scalar(doc.url_path.clone()),
+            );
+        }
+        translations
+            .entry(translated_path(doc))
+            .or_default()
+            .push(i);
     }
-    // check if we should create an jsonfeed file and create it!
-    if let Some(ref path) = context.posts.jsonfeed {
-        let path = path.to_path(&context.destination);
-        create_jsonfeed(
-            &path,
-            &context.posts,
-            &posts,
-            context.site.base_url.as_deref(),
-        )?;
+
+    for siblings in translations.values() {
+        let mut versions: Vec<_> = siblings
+            .iter()
+            .map(|&i| {
+                let doc = &documents[i];
+                let version: liquid::Object = [
+                    ("lang", doc.attributes.get("lang")),
+                    ("title", doc.attributes.get("title")),
+                    ("permalink", doc.attributes.get("permalink")),
+                ]
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.cloned().unwrap_or_default()))
+                .collect();
+                (
+                    doc.front.lang.clone(),
+                    liquid::model::Value::Object(version),
+                )
+            })
+            .collect();
+        versions.sort_by(|a, b| a.0.cmp(&b.0));
+        for &i in siblings {
+            let lang = documents[i].front.lang.clone();
+            let others = versions
+                .iter()
+                .filter(|(other, _)| *other != lang)
+                .map(|(_, version)| version.clone())
+                .collect();
+            documents[i]
+                .attributes
+                .insert("translations".into(), liquid::model::Value::Array(others));
+        }
     }
-    if let Some(ref path) = context.site.sitemap {
-        let path = path.to_path(&context.destination);
-        create_sitemap(&path, &posts, &documents, context.site.base_url.as_deref())?;
+    Ok(())
+}
+
+/// Source path of the document `doc` translates, or its own if it isn't named as a translation
+fn translated_path(doc: &Document) -> relative_path::RelativePathBuf {
+    let untranslated = doc
+        .source_path
+        .file_name()
+        .and_then(cobalt_config::path::split_lang)
+        .filter(|(_, lang)| doc.front.lang.as_deref() == Some(*lang));
+    match untranslated {
+        Some((name, _)) => doc.source_path.with_file_name(name),
+        None => doc.source_path.clone(),
     }
+}
 
-    generate_pages(posts, documents, &context)?;
+/// Move documents dated after the build into `scheduled`, unless future documents are included
+fn hold_back_scheduled(
+    context: &Context,
+    documents: &mut Vec<Document>,
+    scheduled: &mut Vec<Document>,
+) {
+    if context.include_future {
+        return;
+    }
+    let (future, current) = std::mem::take(documents)
+        .into_iter()
+        .partition(|doc| is_scheduled(doc, context.site.time));
+    *documents = current;
+    for doc in future {
+        log::trace!("Skipping scheduled {}", doc.source_path);
+        scheduled.push(doc);
+    }
+}
 
-    // copy all remaining files in the source to the destination
-    // compile SASS along the way
-    for asset_path in asset_paths {
-        context
-            .assets
-            .process(&asset_path.abs_path, &context.destination, &context.minify)?;
+/// Flag documents past their `expiry_date` as `is_expired`, dropping them unless expired documents
+/// are included
+fn handle_expired(context: &Context, documents: &mut Vec<Document>) {
+    documents.retain_mut(|doc| {
+        let expired = is_expired(doc, context.site.time);
+        if expired && !context.include_expired {
+            log::trace!("Skipping expired {}", doc.source_path);
+            return false;
+        }
+        doc.attributes
+            .insert("is_expired".into(), liquid::model::Value::scalar(expired));
+        true
+    });
+}
+
+fn is_expired(doc: &Document, now: cobalt_model::DateTime) -> bool {
+    doc.front.expiry_date.is_some_and(|date| date <= now)
+}
+
+fn is_scheduled(doc: &Document, now: cobalt_model::DateTime) -> bool {
+    doc.front.published_date.is_some_and(|date| now < date)
+}
+
+/// A document that won't be published until `published_date`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledDocument {
+    /// Source file, relative to the source directory
+    pub source: relative_path::RelativePathBuf,
+    pub published_date: cobalt_model::DateTime,
+}
+
+/// Documents a build of `config` holds back because they are dated after the build, soonest first
+pub fn find_scheduled(mut config: Config) -> Result<Vec<ScheduledDocument>> {
+    config.include_future = false;
+    let context = Context::with_config(config)?;
+    let sources = parse_sources(&context)?;
+    let mut scheduled: Vec<_> = sources
+        .scheduled
+        .into_iter()
+        .map(|doc| ScheduledDocument {
+            published_date: doc
+                .front
+                .published_date
+                .expect("only dated documents are scheduled"),
+            source: doc.source_path,
+        })
+        .collect();
+    scheduled.sort_by(|a, b| {
+        a.published_date
+            .cmp(&b.published_date)
+            .then_with(|| a.source.cmp(&b.source))
+    });
+    Ok(scheduled)
+}
+
+/// The sources behind each file a build of `config` writes, relative to the destination
+pub(crate) fn find_output_sources(
+    config: Config,
+) -> Result<BTreeMap<relative_path::RelativePathBuf, Vec<String>>> {
+    let context = Context::with_config(config)?;
+    let sources = parse_sources(&context)?;
+    let simple_collections_data: Vec<_> = sources
+        .collection_docs
+        .iter()
+        .map(|docs| collection_data(docs))
+        .collect();
+    let posts_data = posts_data(&context, &sources.collections, &simple_collections_data);
+    output_sources(
+        &context,
+        &sources.collections,
+        &sources.collection_docs,
+        &sources.documents,
+        posts_data,
+        &sources.asset_paths,
+    )
+}
+
+/// Pagination only considers `posts`
+fn posts_data<'d>(
+    context: &Context,
+    collections: &[&Collection],
+    collections_data: &'d [Vec<liquid::model::Value>],
+) -> &'d [liquid::model::Value] {
+    collections
+        .iter()
+        .position(|c| c.slug == context.posts.slug)
+        .map(|i| collections_data[i].as_slice())
+        .expect("posts are always a collection")
+}
+
+/// The sources behind each file written, relative to the destination
+fn output_sources(
+    context: &Context,
+    collections: &[&Collection],
+    collection_docs: &[Vec<Document>],
+    documents: &[Document],
+    posts_data: &[liquid::model::Value],
+    asset_paths: &[cobalt_core::SourcePath],
+) -> Result<BTreeMap<relative_path::RelativePathBuf, Vec<String>>> {
+    let mut outputs: BTreeMap<relative_path::RelativePathBuf, Vec<String>> = BTreeMap::new();
+    for doc in collection_docs.iter().flatten() {
+        outputs
+            .entry(doc.file_path.clone())
+            .or_default()
+            .push(doc.source_path.to_string());
     }
+    for doc in documents {
+        outputs
+            .entry(doc.file_path.clone())
+            .or_default()
+            .push(doc.source_path.to_string());
+        if doc.front.pagination.is_some() {
+            // page 1 uses frontmatter.permalink instead of paginator.permalink
+            let paginators = pagination::generate_paginators(doc, posts_data, &context.permalinks)?;
+            for paginator in paginators.into_iter().skip(1) {
+                outputs
+                    .entry(permalink::format_url_as_file(&paginator.index_permalink))
+                    .or_default()
+                    .push(format!("{} (paginated)", doc.source_path));
+            }
+        }
+    }
+    for redirect in redirects::collect(collection_docs.iter().flatten().chain(documents)) {
+        outputs
+            .entry(redirect.file_path())
+            .or_default()
+            .push(format!("{} (alias)", redirect.to.source_path));
+    }
+    for path in asset_paths {
+        outputs
+            .entry(context.assets.dest_path(&path.rel_path))
+            .or_default()
+            .push(path.rel_path.to_string());
+    }
+    for (collection, docs) in collections.iter().zip(collection_docs) {
+        if let Some(path) = collection.rss.as_ref() {
+            for (path, _) in localized_feeds(context, path, docs) {
+                outputs
+                    .entry(path)
+                    .or_default()
+                    .push(format!("`{}` RSS feed", collection.slug));
+            }
+        }
+        if let Some(path) = collection.jsonfeed.as_ref() {
+            for (path, _) in localized_feeds(context, path, docs) {
+                outputs
+                    .entry(path)
+                    .or_default()
+                    .push(format!("`{}` jsonfeed", collection.slug));
+            }
+        }
+        if let Some(path) = collection.atom.as_ref() {
+            for (path, _) in localized_feeds(context, path, docs) {
+                outputs
+                    .entry(path)
+                    .or_default()
+                    .push(format!("`{}` Atom feed", collection.slug));
+            }
+        }
+        for feed in term_feeds::term_feeds(collection, docs, &context.permalinks)? {
+            for (path, format) in [(feed.rss, "RSS feed"), (feed.jsonfeed, "jsonfeed")] {
+                if let Some(path) = path {
+                    outputs.entry(path).or_default().push(format!(
+                        "`{}` {} of `{}`",
+                        collection.slug, format, feed.term
+                    ));
+                }
+            }
+        }
+    }
+    if let Some(path) = context.site.sitemap.as_ref() {
+        outputs
+            .entry(path.clone().into_inner())
+            .or_default()
+            .push("sitemap".to_owned());
+        let root_url = context.site.base_url.as_deref().unwrap_or_default();
+        let entries = sitemap_entries(context, collection_docs, documents, posts_data, root_url)?;
+        for path in sitemap::urlset_paths(path, entries.len()) {
+            outputs.entry(path).or_default().push("sitemap".to_owned());
+        }
+    }
+    if let Some(robots) = context.site.robots.as_ref() {
+        outputs
+            .entry(robots.path.clone().into_inner())
+            .or_default()
+            .push("robots.txt".to_owned());
+    }
+    if let Some(search) = context.search.as_ref() {
+        outputs
+            .entry(search.path.clone().into_inner())
+            .or_default()
+            .push("search index".to_owned());
+    }
+    if let Some(redirects) = context.site.redirects.as_ref() {
+        outputs
+            .entry(redirects.path.clone().into_inner())
+            .or_default()
+            .push("redirects".to_owned());
+    }
+    Ok(outputs)
+}
 
+/// Fail if more than one source would be written to the same file
+fn check_output_collisions(
+    outputs: &BTreeMap<relative_path::RelativePathBuf, Vec<String>>,
+) -> Result<()> {
+    let collisions: Vec<_> = outputs
+        .iter()
+        .filter(|(_, sources)| 1 < sources.len())
+        .map(|(path, sources)| format!("`{}` from {}", path, sources.join(", ")))
+        .collect();
+    if !collisions.is_empty() {
+        anyhow::bail!(
+            "Multiple sources would be written to the same file:\n  {}",
+            collisions.join("\n  ")
+        );
+    }
     Ok(())
 }
 
-fn generate_collections_var(
-    posts_data: &[liquid::model::Value],
+/// Where [`OutputsRecord`] is kept, relative to `destination`
+const OUTPUTS_FILE: &str = ".cobalt-outputs.json";
+
+/// Files a build wrote into `destination`, so the next build knows which it may remove
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct OutputsRecord {
+    destination: path::PathBuf,
+    outputs: BTreeSet<String>,
+}
+
+/// Remove files from the destination that the previous build wrote but this one didn't
+///
+/// Files no build recorded writing are reported and left in place.
+fn prune_destination(context: &Context) -> Result<()> {
+    if !context.prune {
+        return Ok(());
+    }
+    let record_path = context.destination.join(OUTPUTS_FILE);
+    let destination = dunce::canonicalize(&context.destination)
+        .with_context(|| anyhow::format_err!("Could not read {}", context.destination.display()))?;
+    let outputs: BTreeSet<String> = context
+        .outputs
+        .lock()
+        .expect("only poisoned if a render panicked")
+        .iter()
+        .filter_map(|output| rel_output(&context.destination, output))
+        .collect();
+    let previous = read_outputs(&record_path)
+        .filter(|previous| previous.destination == destination)
+        .map(|previous| previous.outputs)
+        .unwrap_or_default();
+
+    let source = dunce::canonicalize(context.source_files.root())?;
+    if source.starts_with(&destination) {
+        warn!(
+            "Not removing stale files from `{}` since it contains the source",
+            context.destination.display()
+        );
+    } else {
+        remove_stale(context, &outputs, &previous)?;
+    }
+
+    if !context.prune_dry_run {
+        let record = OutputsRecord {
+            destination,
+            outputs,
+        };
+        files::write_document_file(serde_json::to_string(&record)?, &record_path)?;
+    }
+
+    Ok(())
+}
+
+fn remove_stale(
     context: &Context,
+    outputs: &BTreeSet<String>,
+    previous: &BTreeSet<String>,
+) -> Result<()> {
+    let keep: Vec<_> = context
+        .keep_files
+        .iter()
+        .map(|keep| context.destination.join(keep.as_str()))
+        .chain(std::iter::once(context.destination.join(OUTPUTS_FILE)))
+        .collect();
+    let leftovers = walkdir::WalkDir::new(&context.destination)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| !keep.iter().any(|keep| entry.path() == keep))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_type().is_dir())
+        .map(walkdir::DirEntry::into_path);
+    let mut stale = Vec::new();
+    for file in leftovers {
+        let Some(rel) = rel_output(&context.destination, &file) else {
+            continue;
+        };
+        if outputs.contains(&rel) {
+            continue;
+        }
+        if previous.contains(&rel) {
+            stale.push(file);
+        } else {
+            info!(
+                "Leaving `{}` since no earlier build wrote it; add it to `keep_files` to silence this",
+                file.display()
+            );
+        }
+    }
+    for file in stale {
+        if context.prune_dry_run {
+            info!("Would remove stale `{}`", file.display());
+            continue;
+        }
+        info!("Removing stale `{}`", file.display());
+        fs::remove_file(&file)
+            .with_context(|| anyhow::format_err!("Could not remove {}", file.display()))?;
+        // Clean up directories left empty, stopping at the first one still in use
+        for dir in file.ancestors().skip(1) {
+            if dir == context.destination || fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `path` relative to `destination`, as recorded in an [`OutputsRecord`]
+fn rel_output(destination: &path::Path, path: &path::Path) -> Option<String> {
+    let rel = path.strip_prefix(destination).ok()?;
+    let rel = relative_path::RelativePathBuf::from_path(rel).ok()?;
+    Some(rel.to_string())
+}
+
+fn read_outputs(path: &path::Path) -> Option<OutputsRecord> {
+    let content = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(record) => Some(record),
+        Err(err) => {
+            debug!("Ignoring {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+fn collection_data(docs: &[Document]) -> Vec<liquid::model::Value> {
+    docs.iter()
+        .map(|x| liquid::model::Value::Object(x.attributes.clone()))
+        .collect()
+}
+
+fn generate_collections_var(
+    collections: &[&Collection],
+    collections_data: &[Vec<liquid::model::Value>],
 ) -> (liquid::model::KString, liquid::model::Value) {
-    let mut posts_variable = context.posts.attributes();
-    posts_variable.insert(
-        "pages".into(),
-        liquid::model::Value::Array(posts_data.to_vec()),
-    );
-    let global_collection: liquid::Object = vec![(
-        context.posts.slug.clone(),
-        liquid::model::Value::Object(posts_variable),
-    )]
-    .into_iter()
-    .collect();
+    let global_collection: liquid::Object = collections
+        .iter()
+        .zip(collections_data)
+        .map(|(collection, data)| {
+            let mut variable = collection.attributes();
+            variable.insert("pages".into(), liquid::model::Value::Array(data.clone()));
+            let languages = languages_var(data);
+            if !languages.is_empty() {
+                variable.insert("languages".into(), liquid::model::Value::Object(languages));
+            }
+            (
+                collection.slug.clone(),
+                liquid::model::Value::Object(variable),
+            )
+        })
+        .collect();
     (
         "collections".into(),
         liquid::model::Value::Object(global_collection),
     )
 }
 
+/// A collection's documents grouped by language
+fn languages_var(data: &[liquid::model::Value]) -> liquid::Object {
+    let mut languages = liquid::Object::new();
+    for doc in data {
+        let liquid::model::Value::Object(attributes) = doc else {
+            continue;
+        };
+        let Some(liquid::model::Value::Scalar(lang)) = attributes.get("lang") else {
+            continue;
+        };
+        let docs = languages
+            .entry(lang.clone().into_string())
+            .or_insert_with(|| liquid::model::Value::Array(Vec::new()));
+        if let liquid::model::Value::Array(docs) = docs {
+            docs.push(doc.clone());
+        }
+    }
+    languages
+}
+
+/// Where each of a feed's languages is written, along with its documents
+///
+/// Documents in the site's language, or without one, go to `path`.  Every other language gets its
+/// own feed under a directory named after it.
+fn localized_feeds<'d>(
+    context: &Context,
+    path: &cobalt_config::RelPath,
+    documents: &'d [Document],
+) -> Vec<(relative_path::RelativePathBuf, Vec<&'d Document>)> {
+    let mut feeds: BTreeMap<Option<&str>, Vec<&Document>> = BTreeMap::new();
+    feeds.insert(None, Vec::new());
+    for doc in documents {
+        let lang = doc
+            .front
+            .lang
+            .as_deref()
+            .filter(|lang| context.site.lang.as_deref() != Some(*lang));
+        feeds.entry(lang).or_default().push(doc);
+    }
+    feeds
+        .into_iter()
+        .map(|(lang, docs)| {
+            let path = match lang {
+                Some(lang) => relative_path::RelativePath::new(lang).join(path.as_str()),
+                None => path.clone().into_inner(),
+            };
+            (path, docs)
+        })
+        .collect()
+}
+
+/// A document's output path and HTML, rendered but not yet written
+type RenderedFile = (path::PathBuf, String);
+
+/// Write what each document rendered, in document order, stopping at the first failure like a
+/// serial build would
+fn write_rendered(
+    context: &Context,
+    rendered: impl IntoIterator<Item = (Vec<RenderedFile>, Result<()>)>,
+) -> Result<()> {
+    for (outputs, result) in rendered {
+        for (path, html) in outputs {
+            files::write_document_file(html, &path)?;
+            context.record_output(path);
+        }
+        result?;
+    }
+    Ok(())
+}
+
 fn generate_doc(
     doc: &mut Document,
     context: &Context,
-    global_collection: (liquid::model::KString, liquid::model::Value),
+    global_collection: (&str, &dyn liquid::ValueView),
+    outputs: &mut Vec<RenderedFile>,
 ) -> Result<()> {
-    // Everything done with `globals` is terrible for performance.  liquid#95 allows us to
-    // improve this.
-    let mut globals: liquid::Object = vec![
-        (
-            "site".into(),
-            liquid::model::Value::Object(context.site_attributes.clone()),
-        ),
-        global_collection,
-    ]
-    .into_iter()
-    .collect();
-    globals.insert(
-        "page".into(),
-        liquid::model::Value::Object(doc.attributes.clone()),
-    );
+    // `site` and `collections` are shared by every document, so they're borrowed rather than
+    // copied into each document's globals
+    let (collection_name, collection) = global_collection;
+    let globals = Globals::new()
+        .with("site", &context.site_attributes)
+        .with(collection_name, collection);
+    let page = doc.attributes.clone();
+    let page_globals = Globals::layered_on(&globals).with("page", &page);
     {
         let render_context = RenderContext {
             parser: &context.liquid,
             markdown: &context.markdown,
-            globals: &globals,
+            globals: &page_globals,
             minify: context.minify.clone(),
+            words_per_minute: context.words_per_minute,
         };
 
         doc.render_excerpt(&render_context).with_context(|| {
@@ -214,107 +1067,207 @@ fn generate_doc(
     }
 
     // Refresh `page` with the `excerpt` / `content` attribute
-    globals.insert(
-        "page".into(),
-        liquid::model::Value::Object(doc.attributes.clone()),
-    );
+    let page = doc.attributes.clone();
+    let page_globals = Globals::layered_on(&globals).with("page", &page);
     let render_context = RenderContext {
         parser: &context.liquid,
         markdown: &context.markdown,
-        globals: &globals,
+        globals: &page_globals,
         minify: context.minify.clone(),
+        words_per_minute: context.words_per_minute,
     };
     let doc_html = doc
         .render(&render_context, &context.layouts)
         .with_context(|| anyhow::format_err!("Failed to render for {}", doc.file_path))?;
-    files::write_document_file(doc_html, doc.file_path.to_path(&context.destination))?;
+    let path = doc.file_path.to_path(&context.destination);
+    outputs.push((path, doc_html));
     Ok(())
 }
 
-fn generate_pages(posts: Vec<Document>, documents: Vec<Document>, context: &Context) -> Result<()> {
-    // during post rendering additional attributes such as content were
-    // added to posts. collect them so that non-post documents can access them
-    let posts_data: Vec<liquid::model::Value> = posts
+fn generate_pages(
+    collections: &[&Collection],
+    collection_docs: Vec<Vec<Document>>,
+    mut documents: Vec<Document>,
+    context: &Context,
+    tracker: &incremental::Tracker<'_>,
+) -> Result<Vec<Document>> {
+    // during collection rendering additional attributes such as content were
+    // added to documents. collect them so that pages can access them
+    let collections_data: Vec<Vec<liquid::model::Value>> = collection_docs
         .into_iter()
-        .map(|x| liquid::model::Value::Object(x.attributes))
+        .map(|docs| {
+            docs.into_iter()
+                .map(|x| liquid::model::Value::Object(x.attributes))
+                .collect()
+        })
         .collect();
+    let posts_data = posts_data(context, collections, &collections_data);
+    let global_collection = generate_collections_var(collections, &collections_data);
 
     trace!("Generating other documents");
-    for mut doc in documents {
-        trace!("Generating {}", doc.url_path);
-        if doc.front.pagination.is_some() {
-            let paginators = pagination::generate_paginators(&mut doc, &posts_data)?;
-            // page 1 uses frontmatter.permalink instead of paginator.permalink
-            let mut paginators = paginators.into_iter();
-            let paginator = paginators
-                .next()
-                .expect("We detected pagination enabled but we have no paginator");
-            generate_doc(
-                &mut doc,
+    let rendered: Vec<_> = documents
+        .par_iter_mut()
+        .map(|doc| {
+            let mut outputs = Vec::new();
+            let result = generate_page(
+                doc,
+                posts_data,
+                &global_collection,
                 context,
-                (
-                    "paginator".into(),
-                    liquid::model::Value::Object(paginator.into()),
-                ),
-            )?;
-            for paginator in paginators {
-                let mut doc_page = doc.clone();
-                doc_page.file_path = permalink::format_url_as_file(&paginator.index_permalink);
-                generate_doc(
-                    &mut doc_page,
-                    context,
-                    (
-                        "paginator".into(),
-                        liquid::model::Value::Object(paginator.into()),
-                    ),
-                )?;
+                tracker,
+                &mut outputs,
+            );
+            (outputs, result)
+        })
+        .collect();
+    write_rendered(context, rendered)?;
+    Ok(documents)
+}
+
+fn generate_page(
+    doc: &mut Document,
+    posts_data: &[liquid::model::Value],
+    global_collection: &(liquid::model::KString, liquid::model::Value),
+    context: &Context,
+    tracker: &incremental::Tracker<'_>,
+    outputs: &mut Vec<RenderedFile>,
+) -> Result<()> {
+    let source = doc.source_path.to_string();
+    let inputs = tracker.document_inputs(doc, &context.layouts, true);
+    if let Some(entry) = tracker.reuse(&source, &inputs) {
+        trace!("Skipping unchanged {}", doc.url_path);
+        entry.restore(doc);
+        context.record_outputs(&entry);
+        tracker.record(&source, entry);
+        return Ok(());
+    }
+
+    trace!("Generating {}", doc.url_path);
+    let mut output_paths = vec![doc.file_path.to_string()];
+    if let Some(config) = doc.front.pagination.as_ref() {
+        let term_layout = config.term_layout.clone();
+        let paginators = pagination::generate_paginators(doc, posts_data, &context.permalinks)?;
+        // page 1 uses frontmatter.permalink instead of paginator.permalink
+        let mut paginators = paginators.into_iter();
+        let paginator = paginators
+            .next()
+            .expect("We detected pagination enabled but we have no paginator");
+        let paginator = liquid::model::Value::Object(paginator.into());
+        generate_doc(doc, context, ("paginator", &paginator), outputs)?;
+        for paginator in paginators {
+            let mut doc_page = doc.clone();
+            doc_page.file_path = permalink::format_url_as_file(&paginator.index_permalink);
+            if let Some(layout) = term_layout.as_ref() {
+                doc_page.front.layout = Some(layout.clone());
             }
-        } else {
-            generate_doc(
-                &mut doc,
-                context,
-                generate_collections_var(&posts_data, context),
-            )?;
-        };
+            output_paths.push(doc_page.file_path.to_string());
+            let paginator = liquid::model::Value::Object(paginator.into());
+            generate_doc(&mut doc_page, context, ("paginator", &paginator), outputs)?;
+        }
+    } else {
+        let (name, collection) = global_collection;
+        generate_doc(doc, context, (name, collection), outputs)?;
     }
+    let entry = incremental::Entry::new(inputs, output_paths).with_rendered(doc);
+    tracker.record(&source, entry);
     Ok(())
 }
 
-fn generate_posts(posts: &mut [Document], context: &Context) -> Result<()> {
-    // collect all posts attributes to pass them to other posts for rendering
-    let simple_posts_data: Vec<liquid::model::Value> = posts
-        .iter()
-        .map(|x| liquid::model::Value::Object(x.attributes.clone()))
-        .collect();
+/// Set `page.series` for each document that is part of a series
+fn add_series(docs: &mut [Document]) {
+    let docs_data = collection_data(docs);
+    let series = series::series_vars(docs, &docs_data);
+    for (doc, series) in docs.iter_mut().zip(series) {
+        if let Some(series) = series {
+            doc.attributes.insert("series".into(), series);
+        }
+    }
+}
+
+fn generate_posts(
+    posts: &mut [Document],
+    collection: &Collection,
+    context: &Context,
+    global_collection: &(liquid::model::KString, liquid::model::Value),
+    tracker: &incremental::Tracker<'_>,
+) -> Result<()> {
+    // collect all posts attributes to link each post to its neighbors
+    let simple_posts_data = collection_data(posts);
+    let related = related::rank_related(&simple_posts_data, &collection.related);
 
     trace!("Generating posts");
-    for (i, post) in &mut posts.iter_mut().enumerate() {
-        trace!("Generating {}", post.url_path);
-
-        // posts are in reverse date order, so previous post is the next in the list (+1)
-        let previous = simple_posts_data
-            .get(i + 1)
-            .cloned()
-            .unwrap_or(liquid::model::Value::Nil);
-        post.attributes.insert("previous".into(), previous);
-
-        let next = if i >= 1 {
-            simple_posts_data.get(i - 1)
-        } else {
-            None
-        }
+    let rendered: Vec<_> = posts
+        .par_iter_mut()
+        .enumerate()
+        .map(|(i, post)| {
+            let result = generate_post(
+                i,
+                post,
+                &simple_posts_data,
+                &related[i],
+                global_collection,
+                context,
+                tracker,
+            );
+            match result {
+                Ok(outputs) => (outputs, Ok(())),
+                Err(err) => (Vec::new(), Err(err)),
+            }
+        })
+        .collect();
+    write_rendered(context, rendered)
+}
+
+fn generate_post(
+    i: usize,
+    post: &mut Document,
+    simple_posts_data: &[liquid::model::Value],
+    related: &[usize],
+    global_collection: &(liquid::model::KString, liquid::model::Value),
+    context: &Context,
+    tracker: &incremental::Tracker<'_>,
+) -> Result<Vec<RenderedFile>> {
+    // posts are in reverse date order, so previous post is the next in the list (+1)
+    let previous = simple_posts_data
+        .get(i + 1)
         .cloned()
         .unwrap_or(liquid::model::Value::Nil);
-        post.attributes.insert("next".into(), next);
+    post.attributes.insert("previous".into(), previous);
 
-        generate_doc(
-            post,
-            context,
-            generate_collections_var(&simple_posts_data, context),
-        )?;
+    let next = if i >= 1 {
+        simple_posts_data.get(i - 1)
+    } else {
+        None
     }
+    .cloned()
+    .unwrap_or(liquid::model::Value::Nil);
+    post.attributes.insert("next".into(), next);
 
-    Ok(())
+    let related = related
+        .iter()
+        .map(|&j| simple_posts_data[j].clone())
+        .collect();
+    post.attributes
+        .insert("related".into(), liquid::model::Value::Array(related));
+
+    let source = post.source_path.to_string();
+    let inputs = tracker.document_inputs(post, &context.layouts, false);
+    if let Some(entry) = tracker.reuse(&source, &inputs) {
+        trace!("Skipping unchanged {}", post.url_path);
+        entry.restore(post);
+        context.record_outputs(&entry);
+        tracker.record(&source, entry);
+        return Ok(Vec::new());
+    }
+
+    trace!("Generating {}", post.url_path);
+    let (name, collection) = global_collection;
+    let mut outputs = Vec::new();
+    generate_doc(post, context, (name, collection), &mut outputs)?;
+    let entry =
+        incremental::Entry::new(inputs, vec![post.file_path.to_string()]).with_rendered(post);
+    tracker.record(&source, entry);
+    Ok(outputs)
 }
 
 fn sort_pages(posts: &mut [Document], collection: &Collection) -> Result<()> {
@@ -342,6 +1295,7 @@ fn parse_drafts(
     page_paths: &[cobalt_core::SourcePath],
     documents: &mut Vec<Document>,
     collection: &Collection,
+    context: &Context,
 ) -> Result<()> {
     let dir = &collection.dir;
     let drafts_dir = collection
@@ -358,11 +1312,12 @@ fn parse_drafts(
 
         let default_front = cobalt_config::Frontmatter {
             is_draft: Some(true),
+            lang: file_lang(&file_path.rel_path, page_paths, &context.site),
             ..Default::default()
         }
         .merge(&collection.default);
 
-        let doc = Document::parse(&file_path.abs_path, &new_path, default_front)
+        let doc = Document::parse(file_path, &new_path, default_front, &context.permalinks)
             .with_context(|| anyhow::format_err!("Failed to parse {}", file_path.rel_path))?;
         documents.push(doc);
     }
@@ -372,15 +1327,24 @@ fn parse_drafts(
 fn parse_pages(
     page_paths: &[cobalt_core::SourcePath],
     collection: &Collection,
-    include_drafts: bool,
+    context: &Context,
 ) -> Result<Vec<Document>> {
     let mut documents = vec![];
     for file_path in page_paths {
-        let default_front = collection.default.clone();
+        let default_front = cobalt_config::Frontmatter {
+            lang: file_lang(&file_path.rel_path, page_paths, &context.site),
+            ..Default::default()
+        }
+        .merge(&collection.default);
 
-        let doc = Document::parse(&file_path.abs_path, &file_path.rel_path, default_front)
-            .with_context(|| anyhow::format_err!("Failed to parse {}", file_path.rel_path))?;
-        if !doc.front.is_draft || include_drafts {
+        let doc = Document::parse(
+            file_path,
+            &file_path.rel_path,
+            default_front,
+            &context.permalinks,
+        )
+        .with_context(|| anyhow::format_err!("Failed to parse {}", file_path.rel_path))?;
+        if !doc.front.is_draft || context.include_drafts {
             documents.push(doc);
         } else {
             log::trace!("Skipping draft {}", file_path.rel_path);
@@ -389,67 +1353,79 @@ fn parse_pages(
     Ok(documents)
 }
 
-fn find_layouts(layouts: &path::Path) -> Result<files::Files> {
-    let mut files = files::FilesBuilder::new(layouts)?;
-    files.ignore_hidden(false)?;
-    files.build()
+/// Language of a file named like `post.de.md`, the German version of `post.md`
+///
+/// The code has to be one of the site's languages, or, if the site has a `lang`, the file has to
+/// sit next to the one it translates, so `app.js.liquid` isn't taken to be in `js`.
+fn file_lang(
+    rel_path: &relative_path::RelativePath,
+    paths: &[cobalt_core::SourcePath],
+    site: &cobalt_model::Site,
+) -> Option<liquid::model::KString> {
+    let (translates, lang) = cobalt_config::path::split_lang(rel_path.file_name()?)?;
+    let configured =
+        site.lang.as_deref() == Some(lang) || site.languages.iter().any(|known| known == lang);
+    let translation = site.lang.is_some() && {
+        let original = rel_path.with_file_name(translates);
+        paths.iter().any(|path| path.rel_path == original)
+    };
+    (configured || translation).then(|| liquid::model::KString::from_ref(lang))
 }
 
-fn parse_layouts(files: &files::Files) -> HashMap<String, String> {
-    let (entries, errors): (Vec<_>, Vec<_>) = files
-        .files()
-        .map(|file_path| {
-            let rel_src = file_path
-                .strip_prefix(files.root())
-                .expect("file was found under the root");
-
-            let layout_data = files::read_file(&file_path).with_context(|| {
-                anyhow::format_err!("Failed to load layout {}", rel_src.display())
-            })?;
-
-            let path = rel_src
-                .to_str()
-                .ok_or_else(|| {
-                    anyhow::format_err!("File name not valid liquid path: {}", rel_src.display())
-                })?
-                .to_owned();
-
-            Ok((path, layout_data))
-        })
-        .partition(Result::is_ok);
-
-    for error in errors {
-        warn!("{}", error.expect_err("partition to filter out oks"));
+/// Documents of a feed: those not opting out, cut down to the newest `feed_limit`
+fn feed_documents<'d>(collection: &Collection, documents: &[&'d Document]) -> Vec<&'d Document> {
+    let mut documents: Vec<_> = documents
+        .iter()
+        .copied()
+        .filter(|doc| doc.front.feed)
+        .collect();
+    if let Some(limit) = collection.feed_limit {
+        if limit < documents.len() {
+            let mut newest: Vec<_> = (0..documents.len()).collect();
+            newest.sort_by_key(|&i| std::cmp::Reverse(documents[i].front.published_date));
+            newest.truncate(limit);
+            // keep the collection's order among those kept
+            newest.sort_unstable();
+            documents = newest.into_iter().map(|i| documents[i]).collect();
+        }
     }
-
-    entries
-        .into_iter()
-        .map(|entry| entry.expect("partition to filter out errors"))
-        .collect()
+    documents
 }
 
 // creates a new RSS file with the contents of the site blog
 fn create_rss(
     path: &path::Path,
     collection: &Collection,
-    documents: &[Document],
+    title: &str,
+    documents: &[&Document],
     base_url: Option<&str>,
 ) -> Result<()> {
     debug!("Creating RSS file at {}", path.display());
 
-    let title = &collection.title;
     let description = collection.description.as_deref().unwrap_or("");
     let link = base_url
         .as_ref()
         .ok_or_else(|| anyhow::format_err!("`base_url` is required for RSS support"))?;
 
-    let items: Result<Vec<rss::Item>> = documents.iter().map(|doc| doc.to_rss(link)).collect();
+    let documents = feed_documents(collection, documents);
+    let items: Result<Vec<rss::Item>> = documents
+        .iter()
+        .map(|doc| doc.to_rss(link, collection.rss_full_content))
+        .collect();
     let items = items?;
+    let last_build_date = documents.iter().filter_map(|doc| doc.last_modified).max();
 
     let channel = rss::ChannelBuilder::default()
-        .title(title.as_str().to_owned())
+        .title(title.to_owned())
         .link(link.to_owned())
         .description(description.to_owned())
+        .last_build_date(last_build_date.map(|date| date.to_rfc2822()))
+        .itunes_ext(
+            collection
+                .podcast
+                .as_ref()
+                .map(|podcast| podcast::channel_extension(podcast, link)),
+        )
         .items(items)
         .build();
 
@@ -473,18 +1449,21 @@ fn create_rss(
 fn create_jsonfeed(
     path: &path::Path,
     collection: &Collection,
-    documents: &[Document],
+    title: &str,
+    documents: &[&Document],
     base_url: Option<&str>,
 ) -> Result<()> {
     debug!("Creating jsonfeed file at {}", path.display());
 
-    let title = &collection.title;
     let description = collection.description.as_deref().unwrap_or("");
     let link = base_url
         .as_ref()
         .ok_or_else(|| anyhow::format_err!("`base_url` is required for jsonfeed support"))?;
 
-    let jsonitems = documents.iter().map(|doc| doc.to_jsonfeed(link)).collect();
+    let jsonitems = feed_documents(collection, documents)
+        .iter()
+        .map(|doc| doc.to_jsonfeed(link))
+        .collect();
 
     let feed = Feed {
         title: title.to_string(),
@@ -500,50 +1479,201 @@ fn create_jsonfeed(
     Ok(())
 }
 
-fn create_sitemap(
+// creates a new Atom file with the contents of the site blog
+fn create_atom(
     path: &path::Path,
-    documents: &[Document],
+    rel_path: &relative_path::RelativePath,
+    collection: &Collection,
+    documents: &[&Document],
+    site: &cobalt_model::Site,
+) -> Result<()> {
+    debug!("Creating Atom file at {}", path.display());
+
+    let link = site
+        .base_url
+        .as_deref()
+        .ok_or_else(|| anyhow::format_err!("`base_url` is required for Atom support"))?;
+    let feed_url = format!("{link}/{rel_path}");
+
+    let entries: Vec<_> = feed_documents(collection, documents)
+        .iter()
+        .map(|doc| doc.to_atom(link, site.time))
+        .collect();
+    let updated = entries
+        .iter()
+        .map(|entry| *entry.updated())
+        .max()
+        .unwrap_or_else(|| to_fixed_date_time(site.time));
+    // Atom needs an author for every entry, so the feed vouches for those without one
+    let authors = if entries.iter().all(|entry| !entry.authors().is_empty()) {
+        vec![]
+    } else {
+        let name = site.title.as_ref().unwrap_or(&collection.title);
+        vec![
+            atom_syndication::PersonBuilder::default()
+                .name(name.as_str().to_owned())
+                .build(),
+        ]
+    };
+
+    let feed = atom_syndication::FeedBuilder::default()
+        .title(collection.title.as_str())
+        .subtitle(
+            collection
+                .description
+                .as_deref()
+                .map(atom_syndication::Text::plain),
+        )
+        .id(feed_url.clone())
+        .updated(updated)
+        .authors(authors)
+        .links(vec![
+            atom_syndication::LinkBuilder::default()
+                .href(feed_url)
+                .rel("self".to_owned())
+                .build(),
+            atom_syndication::LinkBuilder::default()
+                .href(link.to_owned())
+                .rel("alternate".to_owned())
+                .build(),
+        ])
+        .entries(entries)
+        .build();
+
+    let atom_string = feed.to_string();
+    trace!("Atom data: {}", atom_string);
+    files::write_document_file(atom_string, path)?;
+
+    Ok(())
+}
+
+fn create_sitemap(
+    context: &Context,
+    path: &cobalt_config::RelPath,
+    collection_docs: &[Vec<Document>],
     documents_pages: &[Document],
-    base_url: Option<&str>,
+    posts_data: &[liquid::model::Value],
 ) -> Result<()> {
-    debug!("Creating sitemap file at {}", path.display());
-    let mut buff = Vec::new();
-    let writer = SiteMapWriter::new(&mut buff);
-    let link = base_url
-        .as_ref()
+    let dest_path = path.to_path(&context.destination);
+    debug!("Creating sitemap file at {}", dest_path.display());
+    let link = context
+        .site
+        .base_url
+        .as_deref()
         .ok_or_else(|| anyhow::format_err!("`base_url` is required for sitemap support"))?;
-    let mut urls = writer.start_urlset()?;
-    for doc in documents {
-        doc.to_sitemap(link, &mut urls)?;
+    let entries = sitemap_entries(context, collection_docs, documents_pages, posts_data, link)?;
+    let urlset_paths = sitemap::urlset_paths(path, entries.len());
+    if urlset_paths.is_empty() {
+        let sitemap = sitemap::urlset(&entries)?;
+        files::write_document_file(sitemap, &dest_path)?;
+        context.record_output(dest_path);
+        return Ok(());
     }
 
-    let link = base_url
-        .as_ref()
-        .ok_or_else(|| anyhow::format_err!("`base_url` is required for sitemap support"))?;
-    for doc in documents_pages {
-        doc.to_sitemap(link, &mut urls)?;
+    let mut sitemaps = Vec::with_capacity(urlset_paths.len());
+    for (rel_path, entries) in urlset_paths.iter().zip(entries.chunks(sitemap::MAX_URLS)) {
+        let sitemap = sitemap::urlset(entries)?;
+        let path = rel_path.to_path(&context.destination);
+        files::write_document_file(sitemap, &path)?;
+        context.record_output(path);
+        let lastmod = entries.iter().filter_map(|entry| entry.lastmod).max();
+        sitemaps.push((format!("{link}/{rel_path}"), lastmod));
+    }
+    let index = sitemap::sitemapindex(&sitemaps)?;
+    files::write_document_file(index, &dest_path)?;
+    context.record_output(dest_path);
+
+    Ok(())
+}
+
+/// Every page listed in the sitemap, the pages of paginated documents included
+fn sitemap_entries(
+    context: &Context,
+    collection_docs: &[Vec<Document>],
+    documents_pages: &[Document],
+    posts_data: &[liquid::model::Value],
+    root_url: &str,
+) -> Result<Vec<sitemap::Entry>> {
+    let mut entries = Vec::new();
+    for doc in collection_docs.iter().flatten().chain(documents_pages) {
+        if !doc.front.sitemap {
+            continue;
+        }
+        let entry = doc.to_sitemap(root_url);
+        let paginators = if doc.front.pagination.is_some() {
+            pagination::generate_paginators(doc, posts_data, &context.permalinks)?
+        } else {
+            Vec::new()
+        };
+        // page 1 is the document itself
+        let pages: Vec<_> = paginators
+            .into_iter()
+            .skip(1)
+            .map(|paginator| sitemap::Entry {
+                loc: format!(
+                    "{}/{}",
+                    root_url,
+                    paginator.index_permalink.trim_start_matches('/')
+                ),
+                alternates: Vec::new(),
+                ..entry.clone()
+            })
+            .collect();
+        entries.push(entry);
+        entries.extend(pages);
     }
-    urls.end()?;
+    Ok(entries)
+}
 
-    files::write_document_file(String::from_utf8(buff)?, path)?;
+fn create_robots(context: &Context, config: &cobalt_config::Robots) -> Result<()> {
+    let path = config.path.to_path(&context.destination);
+    debug!("Creating robots.txt at {}", path.display());
+    let sitemap_url = context
+        .site
+        .base_url
+        .as_deref()
+        .zip(context.site.sitemap.as_ref())
+        .map(|(base_url, sitemap)| format!("{base_url}/{sitemap}"));
+    let robots = robots::robots_txt(config, sitemap_url.as_deref(), context.include_drafts)?;
+    files::write_document_file(robots, &path)?;
+    context.record_output(path);
+    Ok(())
+}
 
+/// Writes a page for each alias, and the list of them all when requested
+fn create_redirects(context: &Context, redirects: &[redirects::Redirect<'_>]) -> Result<()> {
+    for redirect in redirects {
+        let path = redirect.file_path().to_path(&context.destination);
+        trace!("Redirecting {} to {}", redirect.from, redirect.to.url_path);
+        let html = redirect.to_html(context.site.base_url.as_deref());
+        files::write_document_file(html, &path)?;
+        context.record_output(path);
+    }
+    if let Some(config) = context.site.redirects.as_ref() {
+        let path = config.path.to_path(&context.destination);
+        debug!("Creating redirects file at {}", path.display());
+        files::write_document_file(redirects::redirects_file(redirects, config.format)?, &path)?;
+        context.record_output(path);
+    }
     Ok(())
 }
 
 pub fn classify_path<'s>(
     path: &relative_path::RelativePathBuf,
     pages: &'s Collection,
-    posts: &'s Collection,
+    collections: impl IntoIterator<Item = &'s Collection>,
     page_extensions: &[liquid::model::KString],
 ) -> Option<(&'s str, bool)> {
     if ext_contains(page_extensions, path) {
-        if path.starts_with(&posts.dir) {
-            return Some((posts.slug.as_str(), false));
-        }
+        for collection in collections {
+            if path.starts_with(&collection.dir) {
+                return Some((collection.slug.as_str(), false));
+            }
 
-        if let Some(drafts_dir) = posts.drafts_dir.as_ref() {
-            if path.starts_with(drafts_dir) {
-                return Some((posts.slug.as_str(), true));
+            if let Some(drafts_dir) = collection.drafts_dir.as_ref() {
+                if path.starts_with(drafts_dir) {
+                    return Some((collection.slug.as_str(), true));
+                }
             }
         }
 
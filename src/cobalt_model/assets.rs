@@ -42,19 +42,38 @@ pub struct Assets {
 }
 
 impl Assets {
+    /// Whether processing `path` depends on nothing but its own content
+    pub fn is_standalone(&self, path: &path::Path) -> bool {
+        !sass::is_sass_file(path)
+    }
+
+    /// Where `rel_src` is written, relative to the destination
+    pub fn dest_path(
+        &self,
+        rel_src: &relative_path::RelativePath,
+    ) -> relative_path::RelativePathBuf {
+        if sass::is_sass_file(&rel_src.to_path("")) {
+            sass::dest_path(rel_src)
+        } else {
+            rel_src.to_owned()
+        }
+    }
+
+    /// Copy or compile `path` into `dest_root`, returning the file written
     pub fn process(
         &self,
         path: &path::Path,
         dest_root: &path::Path,
         minify: &Minify,
-    ) -> Result<()> {
+    ) -> Result<path::PathBuf> {
         let rel_src = path
             .strip_prefix(&self.source)
             .expect("file was found under the root");
         let dest_path = dest_root.join(rel_src);
         if sass::is_sass_file(path) {
-            self.sass
-                .compile_file(&self.source, dest_root, path, minify)?;
+            return self
+                .sass
+                .compile_file(&self.source, dest_root, path, minify);
         } else if path.extension() == Some(OsStr::new("js")) {
             copy_and_minify_js(path, &dest_path, minify.js)?;
         } else if path.extension() == Some(OsStr::new("css")) {
@@ -62,7 +81,7 @@ impl Assets {
         } else {
             files::copy_file(path, &dest_path)?;
         }
-        Ok(())
+        Ok(dest_path)
     }
 }
 
@@ -14,7 +14,14 @@ pub struct Collection {
     pub order: SortOrder,
     pub rss: Option<cobalt_config::RelPath>,
     pub jsonfeed: Option<cobalt_config::RelPath>,
+    pub atom: Option<cobalt_config::RelPath>,
+    pub feed_limit: Option<usize>,
+    pub rss_full_content: bool,
+    pub tag_feeds: cobalt_config::TermFeeds,
+    pub category_feeds: cobalt_config::TermFeeds,
+    pub podcast: Option<cobalt_config::Podcast>,
     pub publish_date_in_filename: bool,
+    pub related: cobalt_config::Related,
     pub default: Frontmatter,
 }
 
@@ -49,6 +56,36 @@ impl Collection {
         Self::from_config(config, "posts", include_drafts, common_default)
     }
 
+    pub fn from_custom_config(
+        mut config: cobalt_config::Collection,
+        slug: &str,
+        site: &cobalt_config::Site,
+        include_drafts: bool,
+        common_default: &Frontmatter,
+    ) -> Result<Self> {
+        if slug == "pages" || slug == "posts" {
+            anyhow::bail!("Collection `{}` is reserved", slug);
+        }
+        if config
+            .dir
+            .as_ref()
+            .is_some_and(|dir| dir.as_str().is_empty())
+        {
+            anyhow::bail!(
+                "Collection `{}` cannot use the site root as its `dir`",
+                slug
+            );
+        }
+        // Like posts, fall back to `site` so feeds always have a title.
+        if config.title.is_none() {
+            config.title = Some(site.title.clone().unwrap_or_else(|| "".into()));
+        }
+        if config.description.is_none() {
+            config.description = site.description.clone();
+        }
+        Self::from_config(config, slug, include_drafts, common_default)
+    }
+
     fn from_config(
         config: cobalt_config::Collection,
         slug: &str,
@@ -63,8 +100,15 @@ impl Collection {
             order,
             rss,
             jsonfeed,
+            atom,
+            feed_limit,
+            rss_full_content,
+            tag_feeds,
+            category_feeds,
+            podcast,
             default,
             publish_date_in_filename,
+            related,
         } = config;
 
         let title = title.ok_or_else(|| anyhow::format_err!("Collection is missing a `title`"))?;
@@ -73,6 +117,10 @@ impl Collection {
         let dir = dir.unwrap_or_else(|| cobalt_config::RelPath::from_unchecked(slug.as_str()));
         let drafts_dir = if include_drafts { drafts_dir } else { None };
 
+        if let Some(podcast) = &podcast {
+            validate_podcast(podcast, rss.is_some(), &slug)?;
+        }
+
         let default = default.merge(common_default).merge(&Frontmatter {
             collection: Some(slug.clone()),
             ..Default::default()
@@ -87,7 +135,14 @@ impl Collection {
             order,
             rss,
             jsonfeed,
+            atom,
+            feed_limit,
+            rss_full_content,
+            tag_feeds,
+            category_feeds,
+            podcast,
             publish_date_in_filename,
+            related,
             default,
         };
         Ok(new)
@@ -122,6 +177,39 @@ impl Collection {
                 liquid::model::Value::scalar(jsonfeed.as_str().to_owned()),
             );
         }
+        if let Some(atom) = self.atom.as_ref() {
+            attributes.insert(
+                "atom".into(),
+                liquid::model::Value::scalar(atom.as_str().to_owned()),
+            );
+        }
         attributes
     }
 }
+
+fn validate_podcast(podcast: &cobalt_config::Podcast, has_rss: bool, slug: &str) -> Result<()> {
+    if !has_rss {
+        anyhow::bail!("Podcast `{}` needs an `rss` feed", slug);
+    }
+    match podcast.image.as_deref() {
+        None => anyhow::bail!("Podcast `{}` is missing an `image`", slug),
+        Some(image) if !super::frontmatter::is_media_url(image) => {
+            anyhow::bail!("Podcast image `{}` must be an absolute path or URL", image)
+        }
+        Some(_) => {}
+    }
+    if podcast.categories.is_empty() {
+        anyhow::bail!("Podcast `{}` needs at least one category", slug);
+    }
+    if let Some(category) = podcast
+        .categories
+        .iter()
+        .find(|category| category.split('/').count() > 2)
+    {
+        anyhow::bail!(
+            "Podcast category `{}` has more than one subcategory",
+            category
+        );
+    }
+    Ok(())
+}
@@ -11,20 +11,35 @@ use super::assets;
 use super::collection;
 use super::mark;
 use super::site;
+use super::taxonomy;
 use super::template;
 use crate::SyntaxHighlight;
 
+/// Build state kept between `--incremental` builds, relative to the source
+pub const CACHE_DIR: &str = ".cobalt-cache";
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(deny_unknown_fields, default)]
 pub struct Config {
     pub source: path::PathBuf,
     pub destination: path::PathBuf,
     pub ignore: Vec<liquid::model::KString>,
+    /// Paths, relative to `destination`, to leave alone when removing stale output
+    pub keep_files: Vec<liquid::model::KString>,
     pub page_extensions: Vec<liquid::model::KString>,
     pub include_drafts: bool,
+    /// Include documents whose `published_date` is after `site.time`
+    pub include_future: bool,
+    /// Render documents past their `expiry_date` rather than leaving them out
+    pub include_expired: bool,
     pub pages: collection::Collection,
     pub posts: collection::Collection,
+    pub collections: Vec<collection::Collection>,
     pub site: site::Site,
+    pub taxonomies: Vec<taxonomy::Taxonomy>,
+    pub search: Option<cobalt_config::Search>,
+    pub words_per_minute: u32,
+    pub last_modified: cobalt_config::LastModified,
     pub layouts_path: path::PathBuf,
     pub liquid: template::LiquidBuilder,
     pub markdown: mark::MarkdownBuilder,
@@ -32,6 +47,18 @@ pub struct Config {
     pub syntax: std::sync::Arc<SyntaxHighlight>,
     pub assets: assets::AssetsBuilder,
     pub minify: cobalt_config::Minify,
+    /// Number of documents to render in parallel, defaulting to the number of CPUs
+    #[serde(skip)]
+    pub jobs: Option<usize>,
+    /// Where to keep build state between runs, enabling incremental builds
+    #[serde(skip)]
+    pub cache_dir: Option<path::PathBuf>,
+    /// Report stale files in `destination` rather than removing them
+    #[serde(skip)]
+    pub prune_dry_run: bool,
+    /// Remove files from `destination` that an earlier build wrote but this one didn't
+    #[serde(skip)]
+    pub prune: bool,
 }
 
 impl Config {
@@ -42,12 +69,20 @@ impl Config {
             destination,
             abs_dest,
             include_drafts,
-            default,
+            include_future,
+            include_expired,
+            mut default,
             pages,
             posts,
+            collections,
             site,
+            taxonomies,
+            search,
+            words_per_minute,
+            last_modified,
             template_extensions,
             ignore: custom_ignore,
+            keep_files,
             syntax_highlight,
             layouts_dir,
             includes_dir,
@@ -58,6 +93,9 @@ impl Config {
         if include_drafts {
             debug!("Draft mode enabled");
         }
+        if include_future {
+            debug!("Future documents enabled");
+        }
 
         if template_extensions.is_empty() {
             anyhow::bail!("`template_extensions` should not be empty.");
@@ -66,12 +104,85 @@ impl Config {
         let source = source.to_path(&root);
         let destination = abs_dest.unwrap_or_else(|| destination.to_path(root));
 
+        if default.lang.is_none() {
+            default.lang = site.lang.clone();
+        }
+        if default.author.is_none() {
+            default.author = site.author.clone();
+        }
+
+        // `drafts_dir`s are checked even without `--drafts`, so that previews and production
+        // builds accept the same config
+        let mut drafts_dirs: Vec<_> = posts
+            .drafts_dir
+            .iter()
+            .map(|dir| (liquid::model::KString::from_static("posts"), dir.clone()))
+            .collect();
+        drafts_dirs.extend(collections.iter().filter_map(|(slug, collection)| {
+            let dir = collection.drafts_dir.clone()?;
+            Some((liquid::model::KString::from_ref(slug.as_str()), dir))
+        }));
+
         let pages = collection::Collection::from_page_config(pages, &site, &default)?;
 
         let posts =
             collection::Collection::from_post_config(posts, &site, include_drafts, &default)?;
 
-        let site = site::Site::from_config(site);
+        let collections = collections
+            .into_iter()
+            .map(|(slug, collection)| {
+                collection::Collection::from_custom_config(
+                    collection,
+                    slug.as_str(),
+                    &site,
+                    include_drafts,
+                    &default,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let dirs: Vec<_> = std::iter::once(&posts)
+            .chain(collections.iter())
+            .map(|collection| (collection.slug.as_str(), &collection.dir))
+            .chain(drafts_dirs.iter().map(|(slug, dir)| (slug.as_str(), dir)))
+            .collect();
+        for (i, (slug, dir)) in dirs.iter().enumerate() {
+            let overlap = dirs[i + 1..].iter().find(|(other_slug, other_dir)| {
+                other_slug != slug
+                    && (dir.starts_with(other_dir.as_str()) || other_dir.starts_with(dir.as_str()))
+            });
+            if let Some((other_slug, other_dir)) = overlap {
+                anyhow::bail!(
+                    "Collection `{}` (`{}`) overlaps with collection `{}` (`{}`)",
+                    slug,
+                    dir,
+                    other_slug,
+                    other_dir
+                );
+            }
+        }
+
+        let taxonomies = taxonomies
+            .into_iter()
+            .map(|(field, taxonomy)| taxonomy::Taxonomy::from_config(field.as_str(), taxonomy))
+            .collect::<Result<Vec<_>>>()?;
+
+        if let Some(search) = &search {
+            let unknown = search.collections.iter().find(|slug| {
+                std::iter::once(&pages)
+                    .chain(std::iter::once(&posts))
+                    .chain(collections.iter())
+                    .all(|collection| collection.slug != **slug)
+            });
+            if let Some(slug) = unknown {
+                anyhow::bail!("Unknown collection `{}` in `search.collections`", slug);
+            }
+        }
+
+        if words_per_minute == 0 {
+            anyhow::bail!("`words_per_minute` should be greater than 0.");
+        }
+
+        let site = site::Site::from_config(site)?;
 
         let mut ignore: Vec<liquid::model::KString> = vec![".*".into(), "_*".into()];
         if let Ok(rel_dest) = path::Path::new(&destination).strip_prefix(&source) {
@@ -90,7 +201,14 @@ impl Config {
         if let Some(dir) = posts.drafts_dir.as_deref() {
             ignore.push(format!("!/{dir}").into());
         }
+        for collection in &collections {
+            ignore.push(format!("!/{}", collection.dir).into());
+            if let Some(dir) = collection.drafts_dir.as_deref() {
+                ignore.push(format!("!/{dir}").into());
+            }
+        }
         ignore.extend(custom_ignore);
+        ignore.push(format!("/{CACHE_DIR}").into());
 
         let assets = assets::AssetsBuilder::from_config(assets, &source);
 
@@ -123,23 +241,48 @@ impl Config {
             source,
             destination,
             ignore,
+            keep_files,
             page_extensions: template_extensions,
             include_drafts,
+            include_future,
+            include_expired,
             pages,
             posts,
+            collections,
             site,
+            taxonomies,
+            search,
+            words_per_minute,
+            last_modified,
             layouts_path,
             liquid,
             markdown,
             syntax,
             assets,
             minify,
+            jobs: None,
+            cache_dir: None,
+            prune_dry_run: false,
+            prune: false,
         };
 
         Ok(config)
     }
 }
 
+impl Config {
+    /// Collections of documents, beyond `pages`, in classification order.
+    pub fn document_collections(&self) -> impl Iterator<Item = &collection::Collection> {
+        std::iter::once(&self.posts).chain(self.collections.iter())
+    }
+
+    pub fn collection(&self, slug: &str) -> Option<&collection::Collection> {
+        std::iter::once(&self.pages)
+            .chain(self.document_collections())
+            .find(|c| c.slug == slug)
+    }
+}
+
 impl Default for Config {
     fn default() -> Config {
         Config::from_config(cobalt_config::Config::default())
@@ -189,3 +332,61 @@ fn test_build_abs_dest() {
         path::Path::new("hello/world").to_path_buf()
     );
 }
+
+#[test]
+fn test_build_collections() {
+    let mut config = cobalt_config::Config::default();
+    config
+        .collections
+        .insert("docs".into(), cobalt_config::Collection::default());
+    let result = Config::from_config(config).unwrap();
+    assert_eq!(result.collections.len(), 1);
+    assert_eq!(result.collections[0].dir, "docs");
+    assert_eq!(result.collection("docs"), Some(&result.collections[0]));
+}
+
+#[test]
+fn test_build_collections_reserved() {
+    let mut config = cobalt_config::Config::default();
+    config
+        .collections
+        .insert("posts".into(), cobalt_config::Collection::default());
+    assert!(Config::from_config(config).is_err());
+}
+
+#[test]
+fn test_build_collections_overlap() {
+    let mut config = cobalt_config::Config::default();
+    config.collections.insert(
+        "talks".into(),
+        cobalt_config::Collection {
+            dir: Some(cobalt_config::RelPath::from_unchecked("posts/talks")),
+            ..Default::default()
+        },
+    );
+    assert!(Config::from_config(config).is_err());
+}
+
+#[test]
+fn test_build_collections_drafts_overlap() {
+    let mut config = cobalt_config::Config::default();
+    config.collections.insert(
+        "docs".into(),
+        cobalt_config::Collection {
+            drafts_dir: Some(cobalt_config::RelPath::from_unchecked("posts/docs-drafts")),
+            ..Default::default()
+        },
+    );
+    assert!(Config::from_config(config).is_err());
+}
+
+#[test]
+fn test_build_robots_relative_path() {
+    let mut config = cobalt_config::Config::default();
+    config.site.robots = Some(serde_yaml::from_str("rules:\n  - disallow: [private/]").unwrap());
+    let err = Config::from_config(config).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Robots rule path `private/` must be an absolute path"
+    );
+}
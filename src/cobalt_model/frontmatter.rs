@@ -20,6 +20,17 @@ pub struct Frontmatter {
     pub tags: Vec<liquid::model::KString>,
     pub excerpt_separator: liquid::model::KString,
     pub published_date: Option<DateTime>,
+    pub expiry_date: Option<DateTime>,
+    pub updated_date: Option<DateTime>,
+    pub lang: Option<liquid::model::KString>,
+    pub author: Option<liquid::model::KString>,
+    pub series: Option<liquid::model::KString>,
+    pub aliases: Vec<liquid::model::KString>,
+    pub feed: bool,
+    pub enclosure: Option<cobalt_config::Enclosure>,
+    pub sitemap: bool,
+    pub sitemap_priority: Option<cobalt_config::SitemapPriority>,
+    pub sitemap_changefreq: Option<cobalt_config::ChangeFreq>,
     pub format: SourceFormat,
     pub templated: bool,
     pub layout: Option<liquid::model::KString>,
@@ -42,6 +53,17 @@ impl Frontmatter {
             tags,
             excerpt_separator,
             published_date,
+            expiry_date,
+            updated_date,
+            lang,
+            author,
+            series,
+            aliases,
+            feed,
+            enclosure,
+            sitemap,
+            sitemap_priority,
+            sitemap_changefreq,
             format,
             templated,
             layout,
@@ -61,6 +83,13 @@ impl Frontmatter {
                 anyhow::bail!("Empty strings are not allowed in tags");
             }
         }
+        let aliases = aliases.unwrap_or_default();
+        if let Some(alias) = aliases.iter().find(|alias| !alias.starts_with('/')) {
+            anyhow::bail!("Alias `{}` must be an absolute path", alias);
+        }
+        if let Some(enclosure) = &enclosure {
+            validate_enclosure(enclosure)?;
+        }
         let fm = Frontmatter {
             pagination: pagination
                 .and_then(|p| pagination::PaginationConfig::from_config(p, &permalink)),
@@ -73,6 +102,17 @@ impl Frontmatter {
             tags: tags.unwrap_or_default(),
             excerpt_separator: excerpt_separator.unwrap_or_else(|| "\n\n".into()),
             published_date,
+            expiry_date,
+            updated_date,
+            lang,
+            author,
+            series,
+            aliases,
+            feed: feed.unwrap_or(true),
+            enclosure,
+            sitemap: sitemap.unwrap_or(true),
+            sitemap_priority,
+            sitemap_changefreq,
             format: format.unwrap_or_default(),
             #[cfg(feature = "preview_unstable")]
             templated: templated.unwrap_or(false),
@@ -94,6 +134,55 @@ impl Frontmatter {
     }
 }
 
+fn validate_enclosure(enclosure: &cobalt_config::Enclosure) -> Result<()> {
+    if !is_media_url(&enclosure.url) {
+        anyhow::bail!(
+            "Enclosure `{}` must be an absolute path or URL",
+            enclosure.url
+        );
+    }
+    if let Some(image) = enclosure
+        .image
+        .as_ref()
+        .filter(|image| !is_media_url(image))
+    {
+        anyhow::bail!(
+            "Enclosure image `{}` must be an absolute path or URL",
+            image
+        );
+    }
+    if let Some(duration) = enclosure
+        .duration
+        .as_ref()
+        .filter(|duration| !is_duration(duration))
+    {
+        anyhow::bail!(
+            "Enclosure duration `{}` must be `HH:MM:SS`, `MM:SS` or seconds",
+            duration
+        );
+    }
+    if enclosure.episode == Some(0) || enclosure.season == Some(0) {
+        anyhow::bail!("Enclosure `episode` and `season` are numbered from 1");
+    }
+    Ok(())
+}
+
+/// Whether `url` is site-relative, like `/episodes/1.mp3`, or absolute
+pub(crate) fn is_media_url(url: &str) -> bool {
+    url.starts_with('/') || url.contains("://")
+}
+
+/// Whether `duration` is in one of the forms podcast directories accept
+fn is_duration(duration: &str) -> bool {
+    let parts: Vec<_> = duration.split(':').collect();
+    parts.len() <= 3
+        && parts.iter().enumerate().all(|(i, part)| {
+            !part.is_empty()
+                && part.bytes().all(|b| b.is_ascii_digit())
+                && (i == 0 || (part.len() == 2 && part < &"60"))
+        })
+}
+
 impl fmt::Display for Frontmatter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let converted = serde_yaml::to_string(self).expect("should always be valid");
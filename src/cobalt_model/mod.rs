@@ -5,6 +5,7 @@ mod frontmatter;
 mod mark;
 mod sass;
 mod site;
+mod taxonomy;
 mod template;
 mod toc;
 
@@ -24,6 +25,7 @@ pub use cobalt_config::SourceFormat;
 pub use self::assets::Assets;
 pub use self::assets::AssetsBuilder;
 pub use self::collection::Collection;
+pub use self::config::CACHE_DIR;
 pub use self::config::Config;
 pub use self::frontmatter::Frontmatter;
 pub use self::mark::Markdown;
@@ -31,5 +33,6 @@ pub use self::mark::MarkdownBuilder;
 pub use self::sass::SassBuilder;
 pub use self::sass::SassCompiler;
 pub use self::site::Site;
+pub use self::taxonomy::Taxonomy;
 pub use self::template::Liquid;
 pub use self::template::LiquidBuilder;
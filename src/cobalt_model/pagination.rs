@@ -15,6 +15,12 @@ pub struct PaginationConfig {
     pub order: SortOrder,
     pub sort_by: Vec<liquid::model::KString>,
     pub date_index: Vec<DateIndex>,
+    /// Taxonomy's frontmatter list that `Include::Tags` groups posts by, instead of `tags`
+    pub field: Option<liquid::model::KString>,
+    /// Layout of each index's pages, defaulting to the document's layout
+    pub term_layout: Option<liquid::model::KString>,
+    /// Feeds of each index's term, see `term_feeds`
+    pub feeds: cobalt_config::TermFeeds,
 }
 
 impl PaginationConfig {
@@ -49,6 +55,9 @@ impl PaginationConfig {
             order,
             sort_by,
             date_index,
+            field: None,
+            term_layout: None,
+            feeds: Default::default(),
         })
     }
 }
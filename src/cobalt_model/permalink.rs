@@ -1,4 +1,6 @@
-use std::sync::LazyLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::RwLock;
 
 use liquid;
 
@@ -7,14 +9,18 @@ use crate::error::Result;
 pub fn explode_permalink<S: AsRef<str>>(
     permalink: S,
     attributes: &liquid::Object,
+    templates: &Templates,
 ) -> Result<String> {
-    explode_permalink_string(permalink.as_ref(), attributes)
+    explode_permalink_string(permalink.as_ref(), attributes, templates)
 }
 
-fn explode_permalink_string(permalink: &str, attributes: &liquid::Object) -> Result<String> {
-    static PERMALINK_PARSER: LazyLock<liquid::Parser> = LazyLock::new(liquid::Parser::new);
-    let p = PERMALINK_PARSER.parse(permalink)?;
-    let mut p = p.render(attributes)?;
+fn explode_permalink_string(
+    permalink: &str,
+    attributes: &liquid::Object,
+    templates: &Templates,
+) -> Result<String> {
+    let template = templates.get(permalink)?;
+    let mut p = template.render(attributes)?;
 
     // Handle the user doing windows-style
     p = p.replace('\\', "/");
@@ -29,6 +35,35 @@ fn explode_permalink_string(permalink: &str, attributes: &liquid::Object) -> Res
     Ok(p)
 }
 
+/// Permalinks compiled during a build, parsed on first use
+///
+/// Every document in a collection shares the same permalink so this avoids re-parsing it for each
+/// one.
+#[derive(Default)]
+pub struct Templates {
+    parser: liquid::Parser,
+    templates: RwLock<HashMap<String, Arc<liquid::Template>>>,
+}
+
+impl Templates {
+    fn get(&self, permalink: &str) -> Result<Arc<liquid::Template>> {
+        if let Some(template) = self
+            .templates
+            .read()
+            .expect("lock is never poisoned")
+            .get(permalink)
+        {
+            return Ok(template.clone());
+        }
+        let template = Arc::new(self.parser.parse(permalink)?);
+        self.templates
+            .write()
+            .expect("lock is never poisoned")
+            .insert(permalink.to_owned(), template.clone());
+        Ok(template)
+    }
+}
+
 pub fn format_url_as_file<S: AsRef<str>>(permalink: S) -> relative_path::RelativePathBuf {
     format_url_as_file_str(permalink.as_ref())
 }
@@ -60,24 +95,34 @@ mod test {
     #[test]
     fn explode_permalink_relative() {
         let attributes = liquid::Object::new();
-        let actual = explode_permalink("relative/path", &attributes).unwrap();
+        let actual =
+            explode_permalink("relative/path", &attributes, &Templates::default()).unwrap();
         assert_eq!(actual, "relative/path");
     }
 
     #[test]
     fn explode_permalink_absolute() {
         let attributes = liquid::Object::new();
-        let actual = explode_permalink("/abs/path", &attributes).unwrap();
+        let actual = explode_permalink("/abs/path", &attributes, &Templates::default()).unwrap();
         assert_eq!(actual, "abs/path");
     }
 
     #[test]
     fn explode_permalink_blank_substitution() {
         let attributes = liquid::Object::new();
-        let actual = explode_permalink("//path/middle//end", &attributes).unwrap();
+        let actual =
+            explode_permalink("//path/middle//end", &attributes, &Templates::default()).unwrap();
         assert_eq!(actual, "path/middle/end");
     }
 
+    #[test]
+    fn explode_permalink_reuses_template() {
+        let templates = Templates::default();
+        let first = templates.get("/{{ slug }}/").unwrap();
+        let second = templates.get("/{{ slug }}/").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
     #[test]
     fn format_url_as_file_absolute() {
         let actual = format_url_as_file("/hello/world.html");
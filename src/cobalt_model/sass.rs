@@ -48,7 +48,7 @@ impl SassCompiler {
         dest: D,
         file_path: F,
         minify: &Minify,
-    ) -> Result<()> {
+    ) -> Result<path::PathBuf> {
         self.compile_sass_internal(source.as_ref(), dest.as_ref(), file_path.as_ref(), minify)
     }
 
@@ -59,7 +59,7 @@ impl SassCompiler {
         dest: &path::Path,
         file_path: &path::Path,
         minify: &Minify,
-    ) -> Result<()> {
+    ) -> Result<path::PathBuf> {
         let sass_opts = sass_rs::Options {
             include_paths: self.import_dir.iter().cloned().collect(),
             output_style: match self.style {
@@ -95,7 +95,8 @@ impl SassCompiler {
             content
         };
 
-        files::write_document_file(content, dest_file)
+        files::write_document_file(content, &dest_file)?;
+        Ok(dest_file)
     }
 
     #[cfg(not(feature = "sass"))]
@@ -105,12 +106,13 @@ impl SassCompiler {
         dest: &path::Path,
         file_path: &path::Path,
         minify: &Minify,
-    ) -> Result<()> {
+    ) -> Result<path::PathBuf> {
         let rel_src = file_path
             .strip_prefix(source)
             .expect("file was found under the root");
         let dest_file = dest.join(rel_src);
-        files::copy_file(file_path, &dest_file)
+        files::copy_file(file_path, &dest_file)?;
+        Ok(dest_file)
     }
 }
 
@@ -120,6 +122,18 @@ impl Default for SassCompiler {
     }
 }
 
+/// Where a Sass file is written, relative to the destination
+#[cfg(feature = "sass")]
+pub(crate) fn dest_path(rel_src: &relative_path::RelativePath) -> relative_path::RelativePathBuf {
+    rel_src.with_extension("css")
+}
+
+/// Where a Sass file is written, relative to the destination
+#[cfg(not(feature = "sass"))]
+pub(crate) fn dest_path(rel_src: &relative_path::RelativePath) -> relative_path::RelativePathBuf {
+    rel_src.to_owned()
+}
+
 pub(crate) fn is_sass_file(file_path: &path::Path) -> bool {
     file_path.extension() == Some(ffi::OsStr::new("scss"))
         || file_path.extension() == Some(ffi::OsStr::new("sass"))
@@ -21,7 +21,15 @@ pub struct Site {
     pub title: Option<liquid::model::KString>,
     pub description: Option<liquid::model::KString>,
     pub base_url: Option<liquid::model::KString>,
+    /// Language of documents that don't specify one
+    pub lang: Option<liquid::model::KString>,
+    /// Languages, besides `lang`, recognized in file names like `post.de.md`
+    pub languages: Vec<liquid::model::KString>,
+    /// Author of documents that don't name one
+    pub author: Option<liquid::model::KString>,
     pub sitemap: Option<cobalt_config::RelPath>,
+    pub redirects: Option<cobalt_config::Redirects>,
+    pub robots: Option<cobalt_config::Robots>,
     pub data: Option<liquid::Object>,
     pub data_dir: &'static str,
     /// The time at which the `cobalt` binary built the site
@@ -29,12 +37,17 @@ pub struct Site {
 }
 
 impl Site {
-    pub fn from_config(config: cobalt_config::Site) -> Self {
+    pub fn from_config(config: cobalt_config::Site) -> Result<Self> {
         let cobalt_config::Site {
             title,
             description,
             base_url,
+            lang,
+            languages,
+            author,
             sitemap,
+            redirects,
+            robots,
             data,
             data_dir,
         } = config;
@@ -48,15 +61,24 @@ impl Site {
             l
         });
 
-        Self {
+        if let Some(robots) = &robots {
+            validate_robots(robots)?;
+        }
+
+        Ok(Self {
             title,
             description,
             base_url,
+            lang,
+            languages,
+            author,
             sitemap,
+            redirects,
+            robots,
             data,
             data_dir,
             time: DateTime::now(),
-        }
+        })
     }
 
     pub fn load(&self, source: &path::Path) -> Result<liquid::Object> {
@@ -79,6 +101,18 @@ impl Site {
                 liquid::model::Value::scalar(liquid::model::KString::from_ref(base_url)),
             );
         }
+        if let Some(lang) = self.lang.as_ref() {
+            attributes.insert(
+                "lang".into(),
+                liquid::model::Value::scalar(liquid::model::KString::from_ref(lang)),
+            );
+        }
+        if let Some(author) = self.author.as_ref() {
+            attributes.insert(
+                "author".into(),
+                liquid::model::Value::scalar(liquid::model::KString::from_ref(author)),
+            );
+        }
         attributes.insert("time".into(), liquid::model::Value::scalar(self.time));
 
         let mut data = self.data.clone().unwrap_or_default();
@@ -92,6 +126,18 @@ impl Site {
     }
 }
 
+fn validate_robots(robots: &cobalt_config::Robots) -> Result<()> {
+    if let Some(path) = robots
+        .rules
+        .iter()
+        .flat_map(|rule| rule.allow.iter().chain(&rule.disallow))
+        .find(|path| !path.starts_with('/') && !path.starts_with('*'))
+    {
+        anyhow::bail!("Robots rule path `{}` must be an absolute path", path);
+    }
+    Ok(())
+}
+
 fn deep_insert(
     data_map: &mut liquid::Object,
     file_path: &path::Path,
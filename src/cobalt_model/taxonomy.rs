@@ -0,0 +1,103 @@
+use serde::Serialize;
+
+use crate::error::Result;
+
+use super::pagination::Include;
+
+/// Pages listing the terms of a frontmatter list and the posts under each term
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Taxonomy {
+    /// Frontmatter list the terms are read from, falling back to `data`
+    pub field: liquid::model::KString,
+    pub title: liquid::model::KString,
+    pub permalink: cobalt_config::Permalink,
+    pub layout: liquid::model::KString,
+    pub term_layout: liquid::model::KString,
+    pub pagination: cobalt_config::Pagination,
+}
+
+impl Taxonomy {
+    pub fn from_config(field: &str, config: cobalt_config::Taxonomy) -> Result<Self> {
+        let cobalt_config::Taxonomy {
+            title,
+            permalink,
+            layout,
+            term_layout,
+            pagination,
+        } = config;
+
+        let layout = layout
+            .ok_or_else(|| anyhow::format_err!("Taxonomy `{}` is missing a `layout`", field))?;
+        let term_layout = term_layout.unwrap_or_else(|| layout.clone());
+        let title = title.unwrap_or_else(|| cobalt_config::path::titleize_slug(field));
+        let permalink = cobalt_config::Permalink::Explicit(permalink.unwrap_or_else(|| {
+            cobalt_config::ExplicitPermalink::from_unchecked(&format!("/{field}"))
+        }));
+
+        let mut pagination = pagination.unwrap_or_default();
+        if pagination.include.is_some() {
+            anyhow::bail!(
+                "Taxonomy `{}` can't set `pagination.include`, it is derived from the field",
+                field
+            );
+        }
+        // Categories nest, every other list is flat like `tags`
+        pagination.include = Some(if field == "categories" {
+            Include::Categories
+        } else {
+            Include::Tags
+        });
+
+        Ok(Self {
+            field: liquid::model::KString::from_ref(field),
+            title,
+            permalink,
+            layout,
+            term_layout,
+            pagination,
+        })
+    }
+
+    /// Frontmatter of the page listing the terms
+    pub fn frontmatter(&self) -> cobalt_config::Frontmatter {
+        let mut front = cobalt_config::Frontmatter::empty();
+        front.permalink = Some(self.permalink.clone());
+        front.slug = Some(self.field.clone());
+        front.title = Some(self.title.clone());
+        front.layout = Some(self.layout.clone());
+        front.format = Some(cobalt_config::SourceFormat::Raw);
+        front.templated = Some(false);
+        front.pagination = Some(self.pagination.clone());
+        front
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(yaml: &str) -> cobalt_config::Taxonomy {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn defaults_from_field() {
+        let taxonomy = Taxonomy::from_config("series", config("layout: terms.liquid")).unwrap();
+        assert_eq!(taxonomy.title, "Series");
+        assert_eq!(taxonomy.permalink.as_str(), "/series");
+        assert_eq!(taxonomy.term_layout, "terms.liquid");
+        assert_eq!(taxonomy.pagination.include, Some(Include::Tags));
+    }
+
+    #[test]
+    fn categories_nest() {
+        let taxonomy = Taxonomy::from_config("categories", config("layout: terms.liquid")).unwrap();
+        assert_eq!(taxonomy.pagination.include, Some(Include::Categories));
+    }
+
+    #[test]
+    fn layout_is_required() {
+        let err = Taxonomy::from_config("tags", config("title: Tags")).unwrap_err();
+        assert_eq!(err.to_string(), "Taxonomy `tags` is missing a `layout`");
+    }
+}
@@ -1,7 +1,6 @@
 use std::clone::Clone;
 use std::collections::HashMap;
 use std::default::Default;
-use std::path::Path;
 use std::sync::LazyLock;
 
 use anyhow::Context as _;
@@ -12,78 +11,160 @@ use log::trace;
 use regex::Regex;
 
 use crate::cobalt_model;
+use crate::cobalt_model::DateTime;
 use crate::cobalt_model::Minify;
 use crate::cobalt_model::files;
 use crate::cobalt_model::permalink;
 use crate::cobalt_model::slug;
 use crate::error::Result;
+use crate::globals::Globals;
+use crate::layout;
+use crate::layout::Layout;
+use crate::podcast;
+use crate::sitemap;
+use crate::text;
 
 pub(crate) struct RenderContext<'a> {
     pub(crate) parser: &'a cobalt_model::Liquid,
     pub(crate) markdown: &'a cobalt_model::Markdown,
-    pub(crate) globals: &'a Object,
+    pub(crate) globals: &'a dyn liquid::ObjectView,
     pub(crate) minify: Minify,
+    pub(crate) words_per_minute: u32,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct Document {
     pub(crate) url_path: String,
     pub(crate) file_path: relative_path::RelativePathBuf,
+    pub(crate) source_path: relative_path::RelativePathBuf,
     pub(crate) content: liquid::model::KString,
     pub(crate) attributes: Object,
     pub(crate) front: cobalt_model::Frontmatter,
+    /// When the content last changed, see `last_modified::Dates`
+    pub(crate) last_modified: Option<DateTime>,
 }
 
 impl Document {
     pub(crate) fn parse(
-        src_path: &Path,
+        src_path: &cobalt_core::SourcePath,
         rel_path: &relative_path::RelativePath,
         default_front: cobalt_config::Frontmatter,
+        templates: &permalink::Templates,
     ) -> Result<Document> {
         trace!("Parsing `{}`", rel_path);
-        let content = files::read_file(src_path)?;
+        let content = files::read_file(&src_path.abs_path)?;
         let builder = cobalt_config::Document::parse(&content)?;
         let (front, content) = builder.into_parts();
         let front = front.merge_path(rel_path).merge(&default_front);
+        Self::new(
+            src_path.rel_path.clone(),
+            rel_path,
+            front,
+            content,
+            templates,
+        )
+    }
 
+    /// Document for `front` and `content`, found at `rel_path`
+    pub(crate) fn new(
+        source_path: relative_path::RelativePathBuf,
+        rel_path: &relative_path::RelativePath,
+        front: cobalt_config::Frontmatter,
+        content: liquid::model::KString,
+        templates: &permalink::Templates,
+    ) -> Result<Document> {
         let front = cobalt_model::Frontmatter::from_config(front)?;
 
         let (file_path, url_path) = {
             let perma_attributes = permalink_attributes(&front, rel_path);
-            let url_path =
-                permalink::explode_permalink(front.permalink.as_str(), &perma_attributes)
-                    .with_context(|| {
-                        anyhow::format_err!("Failed to create permalink `{}`", front.permalink)
-                    })?;
+            let url_path = permalink::explode_permalink(
+                front.permalink.as_str(),
+                &perma_attributes,
+                templates,
+            )
+            .with_context(|| {
+                anyhow::format_err!("Failed to create permalink `{}`", front.permalink)
+            })?;
             let file_path = permalink::format_url_as_file(&url_path);
             (file_path, url_path)
         };
 
         let doc_attributes = document_attributes(&front, rel_path, url_path.as_ref());
 
-        Ok(Document {
+        let mut doc = Document {
             url_path,
             file_path,
+            source_path,
             content,
             attributes: doc_attributes,
+            last_modified: None,
             front,
-        })
+        };
+        doc.set_last_modified(doc.front.updated_date.or(doc.front.published_date));
+        Ok(doc)
+    }
+
+    /// Record when the content last changed, exposing it as `page.last_modified`
+    pub(crate) fn set_last_modified(&mut self, date: Option<DateTime>) {
+        self.last_modified = date;
+        match date {
+            Some(date) => {
+                self.attributes
+                    .insert("last_modified".into(), Value::scalar(date));
+            }
+            None => {
+                self.attributes.remove("last_modified");
+            }
+        }
     }
 
     /// Metadata for generating RSS feeds
-    pub(crate) fn to_rss(&self, root_url: &str) -> Result<rss::Item> {
+    ///
+    /// With `full_content`, the rendered document goes in `content:encoded` next to the
+    /// description.
+    pub(crate) fn to_rss(&self, root_url: &str, full_content: bool) -> Result<rss::Item> {
         let link = format!("{}/{}", root_url, &self.url_path);
         let guid = rss::GuidBuilder::default()
             .value(link.clone())
             .permalink(true)
             .build();
 
+        let categories: Vec<_> = self
+            .front
+            .tags
+            .iter()
+            .chain(self.front.categories.iter())
+            .map(|term| {
+                rss::CategoryBuilder::default()
+                    .name(term.as_str().to_owned())
+                    .build()
+            })
+            .collect();
+        // RSS' own `author` is an email address, `dc:creator` takes a name
+        let dublin_core = self.front.author.as_ref().map(|author| {
+            rss::extension::dublincore::DublinCoreExtensionBuilder::default()
+                .creators(vec![author.as_str().to_owned()])
+                .build()
+        });
+        let content = full_content
+            .then(|| self.attributes.get("content"))
+            .flatten()
+            .map(|content| content.render().to_string());
+        let enclosure = self.front.enclosure.as_ref();
+
         let item = rss::ItemBuilder::default()
             .title(Some(self.front.title.as_str().to_owned()))
             .link(Some(link))
             .guid(Some(guid))
             .pub_date(self.front.published_date.map(|date| date.to_rfc2822()))
             .description(self.description_to_str())
+            .content(content)
+            .categories(categories)
+            .dublin_core_ext(dublin_core)
+            .enclosure(enclosure.map(|enclosure| podcast::to_rss_enclosure(enclosure, root_url)))
+            .itunes_ext(
+                enclosure.and_then(|enclosure| podcast::item_extension(enclosure, root_url)),
+            )
             .build();
         Ok(item)
     }
@@ -106,34 +187,138 @@ impl Document {
                 .collect()
         };
 
+        let description = self.description_to_str().unwrap_or_else(|| "".into());
+        let summary = text::html_to_text(&description);
+
         jsonfeed::Item {
             id: link.clone(),
             url: Some(link),
             title: Some(self.front.title.as_str().to_owned()),
-            content: jsonfeed::Content::Html(
-                self.description_to_str().unwrap_or_else(|| "".into()),
-            ),
-            date_published: self.front.published_date.map(|date| date.to_rfc2822()),
+            content: jsonfeed::Content::Html(description),
+            summary: (!summary.is_empty()).then_some(summary),
+            date_published: self
+                .front
+                .published_date
+                .map(|date| to_fixed_date_time(date).to_rfc3339()),
+            date_modified: self
+                .last_modified
+                .map(|date| to_fixed_date_time(date).to_rfc3339()),
+            author: self
+                .front
+                .author
+                .as_ref()
+                .map(|author| jsonfeed::Author::new().name(author.as_str())),
             tags: Some(tags),
             ..Default::default()
         }
     }
 
-    pub(crate) fn to_sitemap<T: std::io::Write>(
-        &self,
-        root_url: &str,
-        writer: &mut sitemap::writer::UrlSetWriter<T>,
-    ) -> Result<()> {
+    /// Metadata for generating Atom feeds
+    ///
+    /// Documents without any date are stamped with `build_time`, Atom requiring an `updated` date.
+    pub(crate) fn to_atom(&self, root_url: &str, build_time: DateTime) -> atom_syndication::Entry {
         let link = format!("{}/{}", root_url, &self.url_path);
-        let mut url = sitemap::structs::UrlEntry::builder();
-        url = url.loc(link);
-        if let Some(date) = self.front.published_date {
-            let date = chrono::DateTime::parse_from_rfc2822(&date.to_rfc2822())
-                .expect("chrono/time compatible RFC 2822 implementations");
-            url = url.lastmod(date);
+
+        let terms = if !self.front.tags.is_empty() {
+            &self.front.tags
+        } else {
+            &self.front.categories
+        };
+        let categories: Vec<_> = terms
+            .iter()
+            .map(|term| {
+                atom_syndication::CategoryBuilder::default()
+                    .term(term.as_str().to_owned())
+                    .build()
+            })
+            .collect();
+        let authors: Vec<_> = self
+            .front
+            .author
+            .iter()
+            .map(|author| {
+                atom_syndication::PersonBuilder::default()
+                    .name(author.as_str().to_owned())
+                    .build()
+            })
+            .collect();
+        let content = self
+            .attributes
+            .get("content")
+            .map(|content| content.render().to_string());
+        // a summary repeating the whole content is just noise
+        let summary = self
+            .description_to_str()
+            .filter(|summary| Some(summary) != content.as_ref())
+            .map(atom_syndication::Text::html);
+        let content = content.map(|content| {
+            atom_syndication::ContentBuilder::default()
+                .value(Some(content))
+                .content_type(Some("html".to_owned()))
+                .build()
+        });
+
+        atom_syndication::EntryBuilder::default()
+            .title(self.front.title.as_str())
+            .id(link.clone())
+            .links(vec![
+                atom_syndication::LinkBuilder::default()
+                    .href(link)
+                    .rel("alternate".to_owned())
+                    .build(),
+            ])
+            .updated(to_fixed_date_time(
+                self.last_modified
+                    .or(self.front.published_date)
+                    .unwrap_or(build_time),
+            ))
+            .published(self.front.published_date.map(to_fixed_date_time))
+            .authors(authors)
+            .categories(categories)
+            .summary(summary)
+            .content(content)
+            .build()
+    }
+
+    /// Metadata for generating the sitemap
+    pub(crate) fn to_sitemap(&self, root_url: &str) -> sitemap::Entry {
+        let loc = format!("{}/{}", root_url, &self.url_path);
+        let mut alternates: Vec<_> = self
+            .translations()
+            .into_iter()
+            .map(|(lang, url_path)| (lang, format!("{root_url}/{url_path}")))
+            .collect();
+        if let (Some(lang), false) = (self.front.lang.as_deref(), alternates.is_empty()) {
+            alternates.push((lang.to_owned(), loc.clone()));
+            alternates.sort();
+        }
+        sitemap::Entry {
+            loc,
+            lastmod: self.last_modified,
+            changefreq: self.front.sitemap_changefreq,
+            priority: self.front.sitemap_priority,
+            alternates,
         }
-        writer.url(url)?;
-        Ok(())
+    }
+
+    /// `(lang, url_path)` of this document in other languages
+    fn translations(&self) -> Vec<(String, String)> {
+        let Some(translations) = self
+            .attributes
+            .get("translations")
+            .and_then(|translations| translations.as_array())
+        else {
+            return Vec::new();
+        };
+        translations
+            .values()
+            .filter_map(|translation| {
+                let translation = translation.as_object()?;
+                let lang = translation.get("lang")?.to_kstr().into_string();
+                let url_path = translation.get("permalink")?.to_kstr().into_string();
+                Some((lang, url_path))
+            })
+            .collect()
     }
 
     fn description_to_str(&self) -> Option<String> {
@@ -210,11 +395,21 @@ impl Document {
 
     /// Renders the content and adds it to attributes of the document.
     ///
-    /// When we say "content" we mean only this document without extended layout.
+    /// When we say "content" we mean only this document without extended layout.  Its plain
+    /// text, `word_count` and `reading_time`, in minutes, are added alongside.
     pub(crate) fn render_content(&mut self, context: &RenderContext<'_>) -> Result<()> {
         let (content_html, toc) = self.render_html(&self.content, context)?;
+        let content_text = text::html_to_text(&content_html);
+        let word_count = text::word_count(&content_text);
+        let reading_time = text::reading_time(word_count, context.words_per_minute);
         self.attributes
             .insert("content".into(), Value::scalar(content_html));
+        self.attributes
+            .insert("content_text".into(), Value::scalar(content_text));
+        self.attributes
+            .insert("word_count".into(), Value::scalar(word_count as i64));
+        self.attributes
+            .insert("reading_time".into(), Value::scalar(reading_time as i64));
         if let Some(toc) = toc {
             self.attributes.insert("toc".into(), toc);
         }
@@ -222,46 +417,42 @@ impl Document {
     }
 
     /// Renders the document to an HTML string.
-    ///
-    /// Side effects:
-    ///
-    /// * layout may be inserted to layouts cache
     pub(crate) fn render(
         &mut self,
         context: &RenderContext<'_>,
-        layouts: &HashMap<String, String>,
+        layouts: &HashMap<String, Layout>,
     ) -> Result<String> {
-        if let Some(ref layout) = self.front.layout {
-            let layout_data_ref = layouts.get(layout.as_str()).ok_or_else(|| {
-                anyhow::format_err!(
-                    "Layout {} does not exist (referenced in {}).",
-                    layout,
-                    self.file_path
-                )
-            })?;
+        let page = context
+            .globals
+            .get("page")
+            .and_then(|page| page.as_object())
+            .ok_or_else(|| anyhow::format_err!("Internal error: page isn't in globals"))?;
+        let mut content_html = page
+            .get("content")
+            .ok_or_else(|| anyhow::format_err!("Internal error: page has no content"))?
+            .render()
+            .to_string();
 
-            let template = context
-                .parser
-                .parse(layout_data_ref)
-                .with_context(|| anyhow::format_err!("Failed to parse layout `{}`", layout))?;
-            let content_html = template
-                .render(context.globals)
-                .with_context(|| anyhow::format_err!("Failed to render layout `{}`", layout))?;
-            let content_html = minify_if_enabled(content_html, context, &self.file_path)?;
-            Ok(content_html)
-        } else {
-            let path = &[
-                liquid::model::KStringCow::from_static("page").into(),
-                liquid::model::KStringCow::from_static("content").into(),
-            ];
-            let content_html = liquid::model::try_find(context.globals, path)
-                .ok_or_else(|| anyhow::format_err!("Internal error: page isn't in globals"))?
-                .render()
-                .to_string();
-
-            let content_html = minify_if_enabled(content_html, context, &self.file_path)?;
-            Ok(content_html)
+        if let Some(ref layout) = self.front.layout {
+            // each layout is rendered with the output of the one it wraps as `content` and
+            // `page.content`
+            let chain = layout::layout_chain(layouts, layout, self.file_path.as_str())?;
+            for (name, layout) in chain {
+                let content = Value::scalar(content_html);
+                let page = Globals::layered_on(page).with("content", &content);
+                let globals = Globals::layered_on(context.globals)
+                    .with("content", &content)
+                    .with("page", &page)
+                    .with("layout", &layout.front);
+                content_html = layout
+                    .template
+                    .render(&globals)
+                    .with_context(|| anyhow::format_err!("Failed to render layout `{}`", name))?;
+            }
         }
+
+        let content_html = minify_if_enabled(content_html, context, &self.file_path)?;
+        Ok(content_html)
     }
 }
 
@@ -281,7 +472,14 @@ pub(crate) fn permalink_attributes(
         ),
     );
 
-    let filename = dest_file.file_stem().unwrap_or("").to_owned();
+    let filename = dest_file.file_stem().unwrap_or("");
+    // `post.de.md` is named `post`, like the other versions of it
+    let filename = front
+        .lang
+        .as_deref()
+        .and_then(|lang| filename.strip_suffix(lang)?.strip_suffix('.'))
+        .unwrap_or(filename)
+        .to_owned();
     attributes.insert("name".into(), Value::scalar(filename));
 
     attributes.insert("ext".into(), Value::scalar(".html"));
@@ -380,10 +578,31 @@ fn document_attributes(
     if let Some(ref published_date) = front.published_date {
         attributes.insert("published_date".into(), Value::scalar(*published_date));
     }
+    if let Some(ref lang) = front.lang {
+        attributes.insert("lang".into(), Value::scalar(lang.clone()));
+    }
+    if let Some(ref author) = front.author {
+        attributes.insert("author".into(), Value::scalar(author.clone()));
+    }
+    if let Some(ref expiry_date) = front.expiry_date {
+        attributes.insert("expiry_date".into(), Value::scalar(*expiry_date));
+    }
+    if let Some(ref updated_date) = front.updated_date {
+        attributes.insert("updated_date".into(), Value::scalar(*updated_date));
+    }
+    // replaced by the series' details once the collection is known, see `series::series_vars`
+    if let Some(ref series) = front.series {
+        attributes.insert("series".into(), Value::scalar(series.clone()));
+    }
 
     attributes
 }
 
+pub(crate) fn to_fixed_date_time(date: DateTime) -> chrono::DateTime<chrono::FixedOffset> {
+    chrono::DateTime::parse_from_rfc2822(&date.to_rfc2822())
+        .expect("chrono/time compatible RFC 2822 implementations")
+}
+
 #[cfg(not(feature = "html-minifier"))]
 fn minify_if_enabled(
     html: String,
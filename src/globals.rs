@@ -0,0 +1,152 @@
+use std::fmt;
+
+use liquid::Object;
+use liquid::ObjectView;
+use liquid::ValueView;
+use liquid::model::KStringCow;
+use liquid::model::Value;
+
+/// Top-level variables for rendering a template
+///
+/// Variables are borrowed, rather than copied into a fresh `liquid::Object`, so data shared by
+/// every document (`site`, `collections`) costs nothing per render.  Globals can be layered on
+/// top of another set of globals, with their own variables taking precedence.
+#[derive(Debug, Default)]
+pub(crate) struct Globals<'g> {
+    parent: Option<&'g dyn ObjectView>,
+    variables: Vec<(&'g str, &'g dyn ValueView)>,
+}
+
+impl<'g> Globals<'g> {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn layered_on(parent: &'g dyn ObjectView) -> Self {
+        Self {
+            parent: Some(parent),
+            variables: Vec::new(),
+        }
+    }
+
+    /// Add the variable `name`, replacing any previous value
+    pub(crate) fn with(mut self, name: &'g str, value: &'g dyn ValueView) -> Self {
+        self.variables.retain(|(existing, _)| *existing != name);
+        self.variables.push((name, value));
+        self
+    }
+
+    fn variable(&self, name: &str) -> Option<&'g dyn ValueView> {
+        self.variables
+            .iter()
+            .find(|(existing, _)| *existing == name)
+            .map(|(_, value)| *value)
+    }
+}
+
+impl ValueView for Globals<'_> {
+    fn as_debug(&self) -> &dyn fmt::Debug {
+        self
+    }
+
+    fn render(&self) -> liquid::model::DisplayCow<'_> {
+        liquid::model::DisplayCow::Owned(Box::new(liquid::model::ObjectRender::new(self)))
+    }
+
+    fn source(&self) -> liquid::model::DisplayCow<'_> {
+        liquid::model::DisplayCow::Owned(Box::new(liquid::model::ObjectSource::new(self)))
+    }
+
+    fn type_name(&self) -> &'static str {
+        "object"
+    }
+
+    fn query_state(&self, state: liquid::model::State) -> bool {
+        match state {
+            liquid::model::State::Truthy => true,
+            liquid::model::State::DefaultValue
+            | liquid::model::State::Empty
+            | liquid::model::State::Blank => self.size() == 0,
+        }
+    }
+
+    fn to_kstr(&self) -> KStringCow<'_> {
+        let s = liquid::model::ObjectRender::new(self).to_string();
+        KStringCow::from_string(s)
+    }
+
+    fn to_value(&self) -> Value {
+        let object: Object = self
+            .iter()
+            .map(|(key, value)| (key.into_owned(), value.to_value()))
+            .collect();
+        Value::Object(object)
+    }
+
+    fn as_object(&self) -> Option<&dyn ObjectView> {
+        Some(self)
+    }
+}
+
+impl ObjectView for Globals<'_> {
+    fn as_value(&self) -> &dyn ValueView {
+        self
+    }
+
+    fn size(&self) -> i64 {
+        self.keys().count() as i64
+    }
+
+    fn keys<'k>(&'k self) -> Box<dyn Iterator<Item = KStringCow<'k>> + 'k> {
+        Box::new(self.iter().map(|(key, _)| key))
+    }
+
+    fn values<'k>(&'k self) -> Box<dyn Iterator<Item = &'k dyn ValueView> + 'k> {
+        Box::new(self.iter().map(|(_, value)| value))
+    }
+
+    fn iter<'k>(&'k self) -> Box<dyn Iterator<Item = (KStringCow<'k>, &'k dyn ValueView)> + 'k> {
+        let variables = self
+            .variables
+            .iter()
+            .map(|(name, value)| (KStringCow::from_ref(name), *value));
+        let parent = self
+            .parent
+            .into_iter()
+            .flat_map(|parent| parent.iter())
+            .filter(|(key, _)| self.variable(key.as_str()).is_none());
+        Box::new(variables.chain(parent))
+    }
+
+    fn contains_key(&self, index: &str) -> bool {
+        self.variable(index).is_some()
+            || self.parent.is_some_and(|parent| parent.contains_key(index))
+    }
+
+    fn get<'s>(&'s self, index: &str) -> Option<&'s dyn ValueView> {
+        self.variable(index)
+            .or_else(|| self.parent.and_then(|parent| parent.get(index)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn layers_shadow_parent() {
+        let site = Value::scalar("site");
+        let parent_content = Value::scalar("parent");
+        let content = Value::scalar("child");
+        let parent = Globals::new()
+            .with("site", &site)
+            .with("content", &parent_content);
+        let globals = Globals::layered_on(&parent).with("content", &content);
+
+        assert_eq!(globals.get("content").unwrap().to_kstr(), "child");
+        assert_eq!(globals.get("site").unwrap().to_kstr(), "site");
+        assert!(globals.get("page").is_none());
+        let keys: Vec<_> = globals.keys().map(|key| key.into_owned()).collect();
+        assert_eq!(keys, ["content", "site"]);
+    }
+}
@@ -0,0 +1,496 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::hash::Hasher as _;
+use std::path;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use anyhow::Context as _;
+use log::debug;
+use regex::Regex;
+
+use crate::cobalt_model::Config;
+use crate::cobalt_model::files;
+use crate::document::Document;
+use crate::error::Result;
+use crate::layout::Layout;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Bumped whenever the hashes stored in the manifest are computed differently
+///
+/// Hashes are FNV-1a over little-endian integers, which, unlike `DefaultHasher`, doesn't change
+/// between Rust releases.
+const HASH_FORMAT: u32 = 1;
+
+/// Pseudo-input standing in for the sources of every collection document
+const COLLECTIONS_INPUT: &str = "<collections>";
+/// Pseudo-input for documents rendered past their `expiry_date`, so they get re-rendered when they
+/// expire
+const EXPIRED_INPUT: &str = "<expired>";
+/// Prefix of the pseudo-input holding a document's `last_modified`, which git or the file system
+/// may change without its source changing
+const LAST_MODIFIED_INPUT: &str = "<last_modified>";
+
+/// Attributes added while rendering that other documents may reference
+const RENDERED_ATTRIBUTES: &[&str] = &[
+    "excerpt",
+    "content",
+    "toc",
+    "content_text",
+    "word_count",
+    "reading_time",
+];
+
+/// What the last build produced and from which inputs
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct Manifest {
+    version: String,
+    #[serde(default)]
+    hash_format: u32,
+    config: u64,
+    data: u64,
+    collections: u64,
+    collection_sources: u64,
+    inputs: BTreeMap<String, u64>,
+    sources: BTreeMap<String, Entry>,
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Entry {
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    #[serde(default, skip_serializing_if = "liquid::Object::is_empty")]
+    attributes: liquid::Object,
+}
+
+impl Entry {
+    pub(crate) fn new(inputs: Vec<String>, outputs: Vec<String>) -> Self {
+        Self {
+            inputs,
+            outputs,
+            attributes: Default::default(),
+        }
+    }
+
+    /// Remember the rendered attributes of `doc` so they can be restored when it is skipped
+    pub(crate) fn with_rendered(mut self, doc: &Document) -> Self {
+        self.attributes = RENDERED_ATTRIBUTES
+            .iter()
+            .filter_map(|key| {
+                doc.attributes
+                    .get(*key)
+                    .map(|value| ((*key).into(), value.clone()))
+            })
+            .collect();
+        self
+    }
+
+    /// Files in the destination produced for this source
+    pub(crate) fn outputs(&self) -> impl Iterator<Item = &relative_path::RelativePath> {
+        self.outputs.iter().map(relative_path::RelativePath::new)
+    }
+
+    pub(crate) fn restore(&self, doc: &mut Document) {
+        doc.attributes.extend(self.attributes.clone());
+    }
+}
+
+/// Input hashes for the current build along with the previous build's manifest
+#[derive(Debug)]
+pub(crate) struct Cache {
+    dir: Option<path::PathBuf>,
+    destination: path::PathBuf,
+    previous: Option<Manifest>,
+    config: u64,
+    data: u64,
+    inputs: BTreeMap<String, u64>,
+    layouts_dir: String,
+    includes_dir: String,
+    includes: BTreeMap<String, String>,
+}
+
+impl Cache {
+    pub(crate) fn disabled() -> Self {
+        Self {
+            dir: None,
+            destination: Default::default(),
+            previous: None,
+            config: 0,
+            data: 0,
+            inputs: Default::default(),
+            layouts_dir: Default::default(),
+            includes_dir: Default::default(),
+            includes: Default::default(),
+        }
+    }
+
+    pub(crate) fn load(config: &Config, source_files: &cobalt_core::Source) -> Result<Self> {
+        let Some(dir) = config.cache_dir.clone() else {
+            return Ok(Self::disabled());
+        };
+
+        let source = config.source.as_path();
+        let mut inputs = BTreeMap::new();
+        for path in source_files.iter() {
+            inputs.insert(path.rel_path.to_string(), hash_file(&path.abs_path)?);
+        }
+        let layouts_dir = rel_dir(source, &config.layouts_path)?;
+        insert_dir(&mut inputs, source, &config.layouts_path)?;
+        let includes_dir = rel_dir(source, &config.liquid.includes_path)?;
+        insert_dir(&mut inputs, source, &config.liquid.includes_path)?;
+        let data_path = source.join(config.site.data_dir);
+        let data_dir = rel_dir(source, &data_path)?;
+        insert_dir(&mut inputs, source, &data_path)?;
+
+        let mut hasher = fnv::FnvHasher::default();
+        for (key, hash) in inputs.range(data_dir.clone()..) {
+            if !is_under(key, &data_dir) {
+                break;
+            }
+            hasher.write(key.as_bytes());
+            hasher.write(&hash.to_le_bytes());
+        }
+        let data = hasher.finish();
+
+        let mut includes = BTreeMap::new();
+        for (key, _) in inputs.range(includes_dir.clone()..) {
+            let Some(name) = key.strip_prefix(&includes_dir) else {
+                break;
+            };
+            let Some(name) = name.strip_prefix('/') else {
+                continue;
+            };
+            let content = files::read_file(source.join(key))?;
+            includes.insert(name.to_owned(), content);
+        }
+
+        let config_hash = fingerprint_config(config)?;
+        let previous = read_manifest(&dir.join(MANIFEST_FILE)).filter(|previous| {
+            if previous.version != env!("CARGO_PKG_VERSION") {
+                debug!("Cache was written by another version of cobalt; rebuilding everything");
+                false
+            } else if previous.hash_format != HASH_FORMAT {
+                debug!("Cache hashes are in another format; rebuilding everything");
+                false
+            } else if previous.config != config_hash {
+                debug!("Config changed; rebuilding everything");
+                false
+            } else if previous.data != data {
+                debug!("Site data changed; rebuilding everything");
+                false
+            } else {
+                true
+            }
+        });
+
+        Ok(Self {
+            dir: Some(dir),
+            destination: config.destination.clone(),
+            previous,
+            config: config_hash,
+            data,
+            inputs,
+            layouts_dir,
+            includes_dir,
+            includes,
+        })
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// Where build state is kept between builds, if anywhere
+    pub(crate) fn dir(&self) -> Option<&path::Path> {
+        self.dir.as_deref()
+    }
+
+    /// Start tracking a build once the collection-wide data is known
+    pub(crate) fn tracker<'d>(
+        &self,
+        collection_docs: impl IntoIterator<Item = &'d Document>,
+    ) -> Result<Tracker<'_>> {
+        let mut collections = fnv::FnvHasher::default();
+        let mut collection_sources = fnv::FnvHasher::default();
+        if self.is_enabled() {
+            for doc in collection_docs {
+                let attributes = serde_json::to_string(&serde_json::to_value(&doc.attributes)?)?;
+                collections.write(attributes.as_bytes());
+                let source = doc.source_path.as_str();
+                collection_sources.write(source.as_bytes());
+                let hash = self.inputs.get(source).copied().unwrap_or_default();
+                collection_sources.write(&hash.to_le_bytes());
+            }
+        }
+        let collections = collections.finish();
+        let collection_sources = collection_sources.finish();
+
+        let previous = self.previous.as_ref().filter(|previous| {
+            if previous.collections != collections {
+                debug!("Collections changed; rebuilding everything");
+                false
+            } else {
+                true
+            }
+        });
+
+        Ok(Tracker {
+            cache: self,
+            previous,
+            collections,
+            collection_sources,
+            sources: Default::default(),
+        })
+    }
+}
+
+/// Decides what needs rebuilding and records what was built
+#[derive(Debug)]
+pub(crate) struct Tracker<'c> {
+    cache: &'c Cache,
+    previous: Option<&'c Manifest>,
+    collections: u64,
+    collection_sources: u64,
+    sources: Mutex<BTreeMap<String, Entry>>,
+}
+
+impl Tracker<'_> {
+    /// Inputs that can affect the rendering of `doc`
+    pub(crate) fn document_inputs(
+        &self,
+        doc: &Document,
+        layouts: &HashMap<String, Layout>,
+        uses_collections: bool,
+    ) -> Vec<String> {
+        if !self.cache.is_enabled() {
+            return Vec::new();
+        }
+
+        let mut inputs = vec![doc.source_path.to_string()];
+        let mut templates = Vec::new();
+        if doc.front.templated {
+            templates.push(doc.content.as_str());
+        }
+        // every layout in the chain, stopping at cycles which fail to render anyway
+        let mut next = doc.front.layout.as_deref();
+        while let Some(name) = next {
+            let input = format!("{}/{}", self.cache.layouts_dir, name);
+            if inputs.contains(&input) {
+                break;
+            }
+            inputs.push(input);
+            let layout = layouts.get(name);
+            templates.extend(layout.map(|layout| layout.source.as_str()));
+            next = layout.and_then(|layout| layout.parent.as_deref());
+        }
+        inputs.extend(
+            self.includes_used(templates)
+                .into_iter()
+                .map(|name| format!("{}/{}", self.cache.includes_dir, name)),
+        );
+        if uses_collections {
+            inputs.push(COLLECTIONS_INPUT.to_owned());
+        }
+        if doc.attributes.get("is_expired") == Some(&liquid::model::Value::scalar(true)) {
+            inputs.push(EXPIRED_INPUT.to_owned());
+        }
+        if let Some(date) = doc.last_modified {
+            inputs.push(format!("{LAST_MODIFIED_INPUT}{date}"));
+        }
+        inputs
+    }
+
+    /// Inputs that can affect an asset
+    pub(crate) fn asset_inputs(&self, source: &str) -> Vec<String> {
+        if !self.cache.is_enabled() {
+            return Vec::new();
+        }
+        vec![source.to_owned()]
+    }
+
+    /// The previous build's entry for `source`, if nothing it depends on has changed
+    pub(crate) fn reuse(&self, source: &str, inputs: &[String]) -> Option<Entry> {
+        let previous = self.previous?;
+        let Some(entry) = previous.sources.get(source) else {
+            debug!("Building `{}`: new since the last build", source);
+            return None;
+        };
+        if entry.inputs != inputs {
+            debug!("Rebuilding `{}`: its dependencies changed", source);
+            return None;
+        }
+        let unchanged = inputs.iter().all(|input| {
+            if input == COLLECTIONS_INPUT {
+                previous.collection_sources == self.collection_sources
+            } else if input == EXPIRED_INPUT || input.starts_with(LAST_MODIFIED_INPUT) {
+                // already compared as part of the list of inputs
+                true
+            } else {
+                let current = self.cache.inputs.get(input);
+                current.is_some() && current == previous.inputs.get(input)
+            }
+        });
+        if !unchanged {
+            debug!("Rebuilding `{}`: its inputs changed", source);
+            return None;
+        }
+        let outputs_exist = entry
+            .outputs
+            .iter()
+            .all(|output| self.cache.destination.join(output).exists());
+        if !outputs_exist {
+            debug!("Rebuilding `{}`: its output is missing", source);
+            return None;
+        }
+        Some(entry.clone())
+    }
+
+    pub(crate) fn record(&self, source: &str, entry: Entry) {
+        if !self.cache.is_enabled() {
+            return;
+        }
+        self.sources
+            .lock()
+            .expect("only poisoned if a render panicked")
+            .insert(source.to_owned(), entry);
+    }
+
+    /// Persist what was built for the next build
+    pub(crate) fn save(self) -> Result<()> {
+        let Some(dir) = self.cache.dir.as_ref() else {
+            return Ok(());
+        };
+        let manifest = Manifest {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            hash_format: HASH_FORMAT,
+            config: self.cache.config,
+            data: self.cache.data,
+            collections: self.collections,
+            collection_sources: self.collection_sources,
+            inputs: self.cache.inputs.clone(),
+            sources: self
+                .sources
+                .into_inner()
+                .expect("only poisoned if a render panicked"),
+        };
+        let path = dir.join(MANIFEST_FILE);
+        debug!("Writing build cache to {}", path.display());
+        files::write_document_file(serde_json::to_string(&manifest)?, &path)?;
+        Ok(())
+    }
+
+    fn includes_used<'t>(&self, templates: impl IntoIterator<Item = &'t str>) -> BTreeSet<String> {
+        let mut used = BTreeSet::new();
+        let mut pending: Vec<&str> = templates.into_iter().collect();
+        while let Some(template) = pending.pop() {
+            for name in find_includes(template) {
+                let Some(name) = name else {
+                    // The partial is only known at render time
+                    return self.cache.includes.keys().cloned().collect();
+                };
+                if used.insert(name.to_owned()) {
+                    if let Some(partial) = self.cache.includes.get(name) {
+                        pending.push(partial);
+                    }
+                }
+            }
+        }
+        used
+    }
+}
+
+/// Names of the partials included by `template`, `None` for ones computed at render time
+fn find_includes(template: &str) -> impl Iterator<Item = Option<&str>> {
+    static INCLUDE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#"\{%-?\s*include\s+(?:"([^"]*)"|'([^']*)'|(\S))"#).unwrap());
+    INCLUDE.captures_iter(template).map(|captures| {
+        captures
+            .get(1)
+            .or_else(|| captures.get(2))
+            .map(|name| name.as_str())
+    })
+}
+
+fn fingerprint_config(config: &Config) -> Result<u64> {
+    let mut config = config.clone();
+    // Changes on every run; not a reason to rebuild
+    config.site.time = Default::default();
+    // Round-trip through `serde_json::Value` to get a stable key order
+    let config = serde_json::to_string(&serde_json::to_value(&config)?)?;
+    let mut hasher = fnv::FnvHasher::default();
+    hasher.write(config.as_bytes());
+    Ok(hasher.finish())
+}
+
+fn read_manifest(path: &path::Path) -> Option<Manifest> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(manifest) => Some(manifest),
+        Err(err) => {
+            debug!("Ignoring build cache {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+fn hash_file(path: &path::Path) -> Result<u64> {
+    let content = std::fs::read(path)
+        .with_context(|| anyhow::format_err!("Could not read {}", path.display()))?;
+    let mut hasher = fnv::FnvHasher::default();
+    hasher.write(&content);
+    Ok(hasher.finish())
+}
+
+fn rel_dir(source: &path::Path, dir: &path::Path) -> Result<String> {
+    let rel = dir.strip_prefix(source).unwrap_or(dir);
+    let rel = relative_path::RelativePathBuf::from_path(rel)
+        .with_context(|| anyhow::format_err!("Invalid path {}", dir.display()))?;
+    Ok(rel.to_string())
+}
+
+fn insert_dir(
+    inputs: &mut BTreeMap<String, u64>,
+    source: &path::Path,
+    dir: &path::Path,
+) -> Result<()> {
+    let mut files = files::FilesBuilder::new(dir)?;
+    files.ignore_hidden(false)?;
+    for path in files.build()?.files() {
+        let key = rel_dir(source, &path)?;
+        inputs.insert(key, hash_file(&path)?);
+    }
+    Ok(())
+}
+
+fn is_under(key: &str, dir: &str) -> bool {
+    key.strip_prefix(dir)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_includes_static() {
+        let template = r#"{% include "header.liquid" %} {%- include 'nav/main.liquid' %}"#;
+        let actual: Vec<_> = find_includes(template).collect();
+        assert_eq!(actual, vec![Some("header.liquid"), Some("nav/main.liquid")]);
+    }
+
+    #[test]
+    fn find_includes_dynamic() {
+        let template = r#"{% include page.data.partial %}"#;
+        let actual: Vec<_> = find_includes(template).collect();
+        assert_eq!(actual, vec![None]);
+    }
+
+    #[test]
+    fn is_under_dir() {
+        assert!(is_under("_data/foo.yml", "_data"));
+        assert!(!is_under("_database.yml", "_data"));
+        assert!(!is_under("_data", "_data"));
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::path;
+use std::process::Command;
+
+use cobalt_config::DateTime;
+use cobalt_config::LastModifiedSource;
+
+use crate::cobalt_model::files;
+use crate::document::Document;
+
+/// Dates of the git history as of a commit, kept in the build cache
+const GIT_CACHE_FILE: &str = "git-dates.json";
+
+/// Dates of last modification, looked up in the configured sources
+#[derive(Debug)]
+pub(crate) struct Dates<'c> {
+    sources: &'c [LastModifiedSource],
+    root: &'c path::Path,
+    /// Date of the last commit touching each file, relative to `root`
+    commits: HashMap<String, DateTime>,
+}
+
+impl<'c> Dates<'c> {
+    /// Dates from `config`'s sources, reusing the git history in `cache_dir` while `HEAD` hasn't moved
+    pub(crate) fn new(
+        config: &'c cobalt_config::LastModified,
+        root: &'c path::Path,
+        cache_dir: Option<&path::Path>,
+    ) -> Self {
+        let commits = if config.sources.contains(&LastModifiedSource::Git) {
+            cached_git_commits(root, cache_dir)
+        } else {
+            Default::default()
+        };
+        Self {
+            sources: &config.sources,
+            root,
+            commits,
+        }
+    }
+
+    /// When `doc` was last modified, according to the first source knowing
+    pub(crate) fn of(&self, doc: &Document) -> Option<DateTime> {
+        self.sources.iter().find_map(|source| match source {
+            LastModifiedSource::Frontmatter => doc.front.updated_date.or(doc.front.published_date),
+            LastModifiedSource::Git => self.commits.get(doc.source_path.as_str()).copied(),
+            LastModifiedSource::Mtime => {
+                let path = doc.source_path.to_path(self.root);
+                let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+                Some(to_date_time(modified.into()))
+            }
+        })
+    }
+}
+
+/// The git history as of the commit `head`
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct GitCache {
+    head: String,
+    commits: HashMap<String, DateTime>,
+}
+
+/// [`git_commits`], read from `cache_dir` when it was recorded for the current `HEAD`
+///
+/// Uncommitted changes don't affect commit dates, so the history only changes with `HEAD`.
+fn cached_git_commits(
+    root: &path::Path,
+    cache_dir: Option<&path::Path>,
+) -> HashMap<String, DateTime> {
+    let (Some(cache_dir), Some(head)) = (cache_dir, git_head(root)) else {
+        return git_commits(root);
+    };
+    let path = cache_dir.join(GIT_CACHE_FILE);
+    let cached = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<GitCache>(&content).ok());
+    if let Some(cached) = cached.filter(|cached| cached.head == head) {
+        log::debug!("Reusing git history as of {}", head);
+        return cached.commits;
+    }
+
+    let commits = git_commits(root);
+    let cache = GitCache { head, commits };
+    let written = serde_json::to_string(&cache)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| files::write_document_file(content, &path));
+    if let Err(err) = written {
+        log::debug!("Failed to cache git history in {}: {}", path.display(), err);
+    }
+    cache.commits
+}
+
+/// The commit checked out in the repository holding `root`
+fn git_head(root: &path::Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Date of the latest commit of every file under `root` in its git history
+///
+/// Only commits touching `root` are read, so a site in a larger repository doesn't walk all of it.
+/// Sites outside of a repository, or without `git` installed, have no history.
+fn git_commits(root: &path::Path) -> HashMap<String, DateTime> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "-c",
+            "core.quotePath=false",
+            "log",
+            "--format=%x00%ct",
+            "--name-only",
+            "--relative",
+            "--",
+            ".",
+        ])
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_git_log(&String::from_utf8_lossy(&output.stdout))
+        }
+        Ok(output) => {
+            log::debug!(
+                "No git history for `{}`: {}",
+                root.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            Default::default()
+        }
+        Err(err) => {
+            log::debug!("Failed to run git: {}", err);
+            Default::default()
+        }
+    }
+}
+
+/// Files of `git log --format=%x00%ct --name-only`, with the date of their newest commit
+fn parse_git_log(log: &str) -> HashMap<String, DateTime> {
+    let mut commits = HashMap::new();
+    let mut date = None;
+    for line in log.lines() {
+        if let Some(timestamp) = line.strip_prefix('\0') {
+            date = timestamp
+                .parse()
+                .ok()
+                .and_then(|t| time::OffsetDateTime::from_unix_timestamp(t).ok())
+                .map(to_date_time);
+        } else if let (false, Some(date)) = (line.is_empty(), date) {
+            // newest commits come first
+            commits.entry(line.to_owned()).or_insert(date);
+        }
+    }
+    commits
+}
+
+fn to_date_time(time: time::OffsetDateTime) -> DateTime {
+    let mut date = DateTime::default();
+    *date = time;
+    date
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn newest_commit_wins() {
+        let log = "\x001700000000\n\nposts/a.md\n\n\x001600000000\n\nposts/a.md\nposts/b.md\n";
+        let commits = parse_git_log(log);
+        assert_eq!(commits["posts/a.md"].unix_timestamp(), 1700000000);
+        assert_eq!(commits["posts/b.md"].unix_timestamp(), 1600000000);
+        assert_eq!(commits.len(), 2);
+    }
+
+    #[test]
+    fn frontmatter_prefers_updated_date() {
+        let front: cobalt_config::Frontmatter = serde_yaml::from_str(
+            "published_date: 2016-01-01 21:00:00 +0100\nupdated: 2017-03-04 10:00:00 +0000",
+        )
+        .unwrap();
+        let path = relative_path::RelativePathBuf::from("posts/a.md");
+        let front = front.merge_path(&path);
+        let doc = Document::new(
+            path.clone(),
+            &path,
+            front,
+            Default::default(),
+            &Default::default(),
+        )
+        .unwrap();
+
+        let config = cobalt_config::LastModified::default();
+        let dates = Dates::new(&config, path::Path::new("."), None);
+        assert_eq!(
+            dates.of(&doc),
+            DateTime::from_str("2017-03-04 10:00:00 +0000")
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path;
+
+use anyhow::Context as _;
+use liquid::Object;
+use log::warn;
+
+use crate::cobalt_model;
+use crate::cobalt_model::files;
+use crate::error::Result;
+
+pub(crate) struct Layout {
+    /// Frontmatter, available to the template as `layout`
+    pub(crate) front: Object,
+    /// Layout this one is wrapped in
+    pub(crate) parent: Option<String>,
+    /// Template text, without the frontmatter
+    pub(crate) source: String,
+    pub(crate) template: liquid::Template,
+}
+
+impl Layout {
+    pub(crate) fn parse(content: String, parser: &cobalt_model::Liquid) -> Result<Self> {
+        let (front, source) = cobalt_config::Document::parse(&content)?.into_parts();
+        let parent = front
+            .layout
+            .as_ref()
+            .map(|parent| parent.as_str().to_owned());
+        let front = liquid::model::to_object(&front)?;
+        let source = source.into_string();
+        let template = parser.parse(&source)?;
+        Ok(Self {
+            front,
+            parent,
+            source,
+            template,
+        })
+    }
+}
+
+impl fmt::Debug for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Layout")
+            .field("front", &self.front)
+            .field("parent", &self.parent)
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(crate) fn find_layouts(layouts: &path::Path) -> Result<files::Files> {
+    let mut files = files::FilesBuilder::new(layouts)?;
+    files.ignore_hidden(false)?;
+    files.build()
+}
+
+/// Load and compile every layout, failing on the first one that can't be parsed
+pub(crate) fn parse_layouts(
+    files: &files::Files,
+    parser: &cobalt_model::Liquid,
+) -> Result<HashMap<String, Layout>> {
+    let mut layouts = HashMap::new();
+    for file_path in files.files() {
+        let rel_src = file_path
+            .strip_prefix(files.root())
+            .expect("file was found under the root");
+
+        let layout_data = match files::read_file(&file_path) {
+            Ok(layout_data) => layout_data,
+            Err(err) => {
+                warn!("Failed to load layout {}: {}", rel_src.display(), err);
+                continue;
+            }
+        };
+        let Some(path) = rel_src.to_str() else {
+            warn!("File name not valid liquid path: {}", rel_src.display());
+            continue;
+        };
+
+        let layout = Layout::parse(layout_data, parser)
+            .with_context(|| anyhow::format_err!("Failed to parse layout `{}`", path))?;
+        layouts.insert(path.to_owned(), layout);
+    }
+    Ok(layouts)
+}
+
+/// Layout `name` followed by the layouts wrapping it, innermost first
+pub(crate) fn layout_chain<'l>(
+    layouts: &'l HashMap<String, Layout>,
+    name: &'l str,
+    referenced_in: &str,
+) -> Result<Vec<(&'l str, &'l Layout)>> {
+    let mut chain: Vec<(&str, &Layout)> = Vec::new();
+    let mut next = Some((name, referenced_in));
+    while let Some((name, referenced_in)) = next {
+        if chain.iter().any(|(seen, _)| *seen == name) {
+            let cycle: Vec<_> = chain
+                .iter()
+                .map(|(name, _)| *name)
+                .chain(std::iter::once(name))
+                .collect();
+            anyhow::bail!("Layouts form a cycle: {}", cycle.join(" -> "));
+        }
+        let layout = layouts.get(name).ok_or_else(|| {
+            anyhow::format_err!(
+                "Layout {} does not exist (referenced in {}).",
+                name,
+                referenced_in
+            )
+        })?;
+        chain.push((name, layout));
+        next = layout.parent.as_deref().map(|parent| (parent, name));
+    }
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parser() -> cobalt_model::Liquid {
+        cobalt_model::LiquidBuilder {
+            includes_path: "_includes".into(),
+            theme: None,
+            syntax: Default::default(),
+        }
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_without_frontmatter() {
+        let layout = Layout::parse("<p>{{ content }}</p>\n".to_owned(), &parser()).unwrap();
+        assert!(layout.front.is_empty());
+        assert_eq!(layout.parent, None);
+        assert_eq!(layout.source, "<p>{{ content }}</p>\n");
+    }
+
+    #[test]
+    fn parse_with_parent() {
+        let layout = Layout::parse(
+            "---\nlayout: default.liquid\ntitle: Post\n---\n{{ content }}".to_owned(),
+            &parser(),
+        )
+        .unwrap();
+        assert_eq!(layout.parent.as_deref(), Some("default.liquid"));
+        assert_eq!(
+            layout.front.get("title"),
+            Some(&liquid::model::Value::scalar("Post"))
+        );
+        assert_eq!(layout.source, "{{ content }}");
+    }
+
+    #[test]
+    fn chain_detects_cycles() {
+        let parser = parser();
+        let layouts: HashMap<_, _> = [("a.liquid", "b.liquid"), ("b.liquid", "a.liquid")]
+            .into_iter()
+            .map(|(name, parent)| {
+                let content = format!("---\nlayout: {parent}\n---\n{{{{ content }}}}");
+                (name.to_owned(), Layout::parse(content, &parser).unwrap())
+            })
+            .collect();
+        let err = layout_chain(&layouts, "a.liquid", "index.md").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Layouts form a cycle: a.liquid -> b.liquid -> a.liquid"
+        );
+    }
+}
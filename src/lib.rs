@@ -2,18 +2,37 @@
 #![warn(clippy::print_stderr)]
 #![warn(clippy::print_stdout)]
 
+pub use crate::cobalt::ScheduledDocument;
 pub use crate::cobalt::build;
 pub use crate::cobalt::classify_path;
+pub use crate::cobalt::find_scheduled;
 pub use crate::cobalt_model::Config;
 pub use crate::error::Error;
+pub use crate::links::BrokenLink;
+pub use crate::links::LinkError;
+pub use crate::links::check_links;
 
 pub mod cobalt_model;
 pub mod error;
 
 mod cobalt;
 mod document;
+mod globals;
+mod incremental;
+mod last_modified;
+mod layout;
+mod links;
 
 mod pagination;
+mod podcast;
+mod redirects;
+mod related;
+mod robots;
+mod search;
+mod series;
+mod sitemap;
 mod syntax_highlight;
+mod term_feeds;
+mod text;
 
 pub use crate::syntax_highlight::SyntaxHighlight;
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::path;
+use std::sync::LazyLock;
+
+use anyhow::Context as _;
+use regex::Regex;
+
+use crate::cobalt_model::Config;
+use crate::cobalt_model::files;
+use crate::error::Result;
+
+/// A link in the generated site that doesn't lead anywhere
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// Source files that produced the page containing the link
+    pub source: String,
+    /// Page containing the link, relative to the destination
+    pub page: relative_path::RelativePathBuf,
+    /// The link as written in the page
+    pub link: String,
+    pub reason: LinkError,
+}
+
+impl fmt::Display for BrokenLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: `{}` {}", self.source, self.link, self.reason)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LinkError {
+    /// The target is outside of the destination
+    OutsideSite,
+    /// Nothing was generated at the target
+    Missing,
+    /// The target exists but has no element with the fragment's id
+    MissingAnchor,
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutsideSite => write!(f, "points outside of the site"),
+            Self::Missing => write!(f, "does not exist"),
+            Self::MissingAnchor => write!(f, "has no matching anchor"),
+        }
+    }
+}
+
+/// Find internal links in the generated site that don't resolve
+///
+/// Links are resolved against the destination, treating `site.base_url` as its root.
+pub fn check_links(config: Config) -> Result<Vec<BrokenLink>> {
+    let destination = config.destination.clone();
+    let base_url = config
+        .site
+        .base_url
+        .as_deref()
+        .map(|url| url.trim_end_matches('/').to_owned());
+    let sources = crate::cobalt::find_output_sources(config)?;
+
+    let mut pages = files::FilesBuilder::new(&destination)?;
+    pages.add_extension("html")?;
+    pages.add_extension("htm")?;
+    let pages = pages.build()?;
+
+    let mut checker = Checker {
+        destination: &destination,
+        base_url: base_url.as_deref(),
+        anchors: Default::default(),
+    };
+    let mut broken = Vec::new();
+    for path in pages.files() {
+        let page = path
+            .strip_prefix(&destination)
+            .ok()
+            .and_then(|rel| relative_path::RelativePathBuf::from_path(rel).ok())
+            .expect("file was found under the destination");
+        let html = files::read_file(&path)
+            .with_context(|| anyhow::format_err!("Could not read {}", path.display()))?;
+        for link in find_links(&html) {
+            if let Some(reason) = checker.check(&page, &link)? {
+                let source = sources
+                    .get(&page)
+                    .map(|sources| sources.join(", "))
+                    .unwrap_or_else(|| page.to_string());
+                broken.push(BrokenLink {
+                    source,
+                    page: page.clone(),
+                    link,
+                    reason,
+                });
+            }
+        }
+    }
+    Ok(broken)
+}
+
+struct Checker<'s> {
+    destination: &'s path::Path,
+    base_url: Option<&'s str>,
+    anchors: HashMap<relative_path::RelativePathBuf, HashSet<String>>,
+}
+
+impl Checker<'_> {
+    fn check(
+        &mut self,
+        page: &relative_path::RelativePath,
+        link: &str,
+    ) -> Result<Option<LinkError>> {
+        let link = self
+            .base_url
+            .and_then(|base_url| link.strip_prefix(base_url))
+            .filter(|rest| rest.is_empty() || rest.starts_with(['/', '?', '#']))
+            .map(|rest| if rest.is_empty() { "/" } else { rest })
+            .unwrap_or(link);
+        if is_external(link) {
+            return Ok(None);
+        }
+
+        let (link, fragment) = link.split_once('#').unwrap_or((link, ""));
+        let link = link.split_once('?').map(|(link, _)| link).unwrap_or(link);
+        let link = percent_encoding::percent_decode_str(link).decode_utf8_lossy();
+        let target = if link.is_empty() {
+            page.to_owned()
+        } else if let Some(absolute) = link.strip_prefix('/') {
+            relative_path::RelativePath::new(absolute).normalize()
+        } else {
+            page.parent()
+                .unwrap_or_else(|| relative_path::RelativePath::new(""))
+                .join_normalized(link.as_ref())
+        };
+        if target.as_str().starts_with("..") {
+            return Ok(Some(LinkError::OutsideSite));
+        }
+        let target = if link.ends_with('/') || target.to_path(self.destination).is_dir() {
+            target.join("index.html")
+        } else {
+            target
+        };
+        if !target.to_path(self.destination).is_file() {
+            return Ok(Some(LinkError::Missing));
+        }
+
+        if fragment.is_empty() || !is_html(&target) {
+            return Ok(None);
+        }
+        if !self.anchors.contains_key(&target) {
+            let html = files::read_file(target.to_path(self.destination))?;
+            self.anchors.insert(target.clone(), find_anchors(&html));
+        }
+        if self.anchors[&target].contains(
+            percent_encoding::percent_decode_str(fragment)
+                .decode_utf8_lossy()
+                .as_ref(),
+        ) {
+            Ok(None)
+        } else {
+            Ok(Some(LinkError::MissingAnchor))
+        }
+    }
+}
+
+static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[a-zA-Z][^>]*>").unwrap());
+static ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\s(href|src|id|name)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+});
+
+/// `href` and `src` attributes of every tag
+fn find_links(html: &str) -> Vec<String> {
+    attributes(html, &["href", "src"])
+}
+
+/// Fragments a page can be linked to with
+fn find_anchors(html: &str) -> HashSet<String> {
+    attributes(html, &["id", "name"]).into_iter().collect()
+}
+
+fn attributes(html: &str, names: &[&str]) -> Vec<String> {
+    // Comments and the bodies of scripts and styles can contain anything that looks like a tag
+    static SKIPPED: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?is)<!--.*?-->|(<script\b[^>]*>).*?</script>|(<style\b[^>]*>).*?</style>")
+            .unwrap()
+    });
+    let html = SKIPPED.replace_all(html, "$1$2");
+    TAG.find_iter(&html)
+        .flat_map(|tag| ATTRIBUTE.captures_iter(tag.as_str()))
+        .filter(|captures| {
+            names
+                .iter()
+                .any(|name| captures[1].eq_ignore_ascii_case(name))
+        })
+        .filter_map(|captures| {
+            captures
+                .get(2)
+                .or_else(|| captures.get(3))
+                .or_else(|| captures.get(4))
+        })
+        .map(|value| value.as_str().replace("&amp;", "&"))
+        .collect()
+}
+
+fn is_external(link: &str) -> bool {
+    static SCHEME: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*:").unwrap());
+    link.starts_with("//") || SCHEME.is_match(link)
+}
+
+fn is_html(path: &relative_path::RelativePath) -> bool {
+    matches!(path.extension(), Some("html" | "htm"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_links_skips_scripts_and_comments() {
+        let html = r#"<a href="/a.html">A</a><!-- <a href="/b.html"> -->
+<script src="/app.js">let s = '<a href="/c.html">';</script>
+<img class=x src=/d.png>"#;
+        assert_eq!(find_links(html), vec!["/a.html", "/app.js", "/d.png"]);
+    }
+
+    #[test]
+    fn find_anchors_ids_and_names() {
+        let html = r#"<h1 id="intro">Intro</h1><a name='legacy'></a>"#;
+        let expected: HashSet<_> = ["intro".to_owned(), "legacy".to_owned()].into();
+        assert_eq!(find_anchors(html), expected);
+    }
+
+    #[test]
+    fn is_external_schemes() {
+        assert!(is_external("https://example.com"));
+        assert!(is_external("mailto:me@example.com"));
+        assert!(is_external("//cdn.example.com/x.js"));
+        assert!(!is_external("/posts/a.html"));
+        assert!(!is_external("a.html#b:c"));
+    }
+}
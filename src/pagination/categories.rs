@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+use crate::cobalt_model::permalink;
 use crate::document::Document;
 
 use super::{
@@ -12,9 +13,10 @@ pub(crate) fn create_categories_paginators(
     all_posts: &[&liquid::model::Value],
     doc: &Document,
     pagination_cfg: &PaginationConfig,
+    templates: &permalink::Templates,
 ) -> Result<Vec<Paginator>> {
     let mut root_cat = distribute_posts_by_categories(all_posts)?;
-    let paginators_holder = walk_categories(&mut root_cat, pagination_cfg, doc)?;
+    let paginators_holder = walk_categories(&mut root_cat, pagination_cfg, doc, templates)?;
     Ok(paginators_holder)
 }
 
@@ -77,6 +79,7 @@ fn walk_categories(
     category: &mut Category<'_>,
     config: &PaginationConfig,
     doc: &Document,
+    templates: &permalink::Templates,
 ) -> Result<Vec<Paginator>> {
     let mut cur_cat_paginators_holder: Vec<Paginator> = vec![];
     if !category.cat_path.is_empty() {
@@ -86,6 +89,7 @@ fn walk_categories(
             doc,
             config,
             Some(&liquid::model::Value::array(category.cat_path.clone())),
+            templates,
         )?;
         if !cur_cat_paginators.is_empty() {
             cur_cat_paginators_holder.extend(cur_cat_paginators);
@@ -96,11 +100,18 @@ fn walk_categories(
             };
             cur_cat_paginators_holder.push(p);
         }
+        let cat_path: Vec<_> = category
+            .cat_path
+            .iter()
+            .map(|level| level.to_kstr().into_string())
+            .collect();
+        let cat_path: Vec<_> = cat_path.iter().map(String::as_str).collect();
+        cur_cat_paginators_holder[0].set_feeds(config, "category", &cat_path, templates)?;
     } else {
         cur_cat_paginators_holder.push(Paginator::default());
     }
     for c in category.sub_cats.values_mut() {
-        let mut sub_paginators_holder = walk_categories(c, config, doc)?;
+        let mut sub_paginators_holder = walk_categories(c, config, doc, templates)?;
 
         if let Some(indexes) = cur_cat_paginators_holder[0].indexes.as_mut() {
             indexes.push(sub_paginators_holder[0].clone());
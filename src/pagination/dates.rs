@@ -1,5 +1,6 @@
 use crate::cobalt_model::DateTime;
 use crate::cobalt_model::pagination::DateIndex;
+use crate::cobalt_model::permalink;
 use crate::document::Document;
 
 use super::{
@@ -12,9 +13,10 @@ pub(crate) fn create_dates_paginators(
     all_posts: &[&liquid::model::Value],
     doc: &Document,
     pagination_cfg: &PaginationConfig,
+    templates: &permalink::Templates,
 ) -> Result<Vec<Paginator>> {
     let mut root_date = distribute_posts_by_dates(all_posts, pagination_cfg)?;
-    walk_dates(&mut root_date, pagination_cfg, doc, None)
+    walk_dates(&mut root_date, pagination_cfg, doc, None, templates)
 }
 
 fn distribute_posts_by_dates<'a>(
@@ -124,6 +126,7 @@ fn walk_dates(
     config: &PaginationConfig,
     doc: &Document,
     parent_dates: Option<Vec<DateIndexHolder<'_>>>,
+    templates: &permalink::Templates,
 ) -> Result<Vec<Paginator>> {
     let mut cur_date_holder_paginators: Vec<Paginator> = vec![];
     let mut current_date = parent_dates.unwrap_or_default();
@@ -131,8 +134,13 @@ fn walk_dates(
         sort_posts(&mut date_holder.posts, config);
         current_date.push(date_holder.clone());
         let index_title = liquid::model::Value::array(date_fields_to_array(&current_date));
-        let cur_date_paginators =
-            create_all_paginators(&date_holder.posts, doc, config, Some(&index_title))?;
+        let cur_date_paginators = create_all_paginators(
+            &date_holder.posts,
+            doc,
+            config,
+            Some(&index_title),
+            templates,
+        )?;
         if !cur_date_paginators.is_empty() {
             cur_date_holder_paginators.extend(cur_date_paginators);
         } else {
@@ -146,7 +154,8 @@ fn walk_dates(
         cur_date_holder_paginators.push(Paginator::default());
     }
     for dh in &mut date_holder.sub_date {
-        let mut sub_paginators_holder = walk_dates(dh, config, doc, Some(current_date.clone()))?;
+        let mut sub_paginators_holder =
+            walk_dates(dh, config, doc, Some(current_date.clone()), templates)?;
 
         if let Some(indexes) = cur_date_holder_paginators[0].indexes.as_mut() {
             indexes.push(sub_paginators_holder[0].clone());
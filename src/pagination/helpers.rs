@@ -11,6 +11,16 @@ pub(crate) fn extract_tags(value: &dyn liquid::ValueView) -> Option<&dyn liquid:
     v.as_array()
 }
 
+/// List `key` of a post, falling back to the post's `data`
+pub(crate) fn extract_list<'a>(
+    value: &'a dyn liquid::ValueView,
+    key: &str,
+) -> Option<&'a dyn liquid::model::ArrayView> {
+    extract_value(value, key)
+        .and_then(|v| v.as_array())
+        .or_else(|| extract_value(extract_value(value, "data")?, key)?.as_array())
+}
+
 pub(crate) fn extract_categories(
     value: &dyn liquid::ValueView,
 ) -> Option<&dyn liquid::model::ArrayView> {
@@ -22,3 +32,19 @@ fn extract_value<'v>(v: &'v dyn liquid::ValueView, key: &str) -> Option<&'v dyn
     let o = v.as_object()?;
     o.get(key)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extract_list_falls_back_to_data_list() {
+        let post = liquid::object!({
+            "series": {"title": "Rust"},
+            "data": {"series": ["rust"]},
+        });
+        let list = extract_list(&post, "series").unwrap();
+        assert_eq!(list.size(), 1);
+        assert_eq!(list.first().unwrap().to_kstr(), "rust");
+    }
+}
@@ -18,11 +18,13 @@ mod helpers;
 mod paginator;
 mod tags;
 
+pub(crate) use helpers::extract_list;
 use paginator::Paginator;
 
 pub(crate) fn generate_paginators(
-    doc: &mut Document,
+    doc: &Document,
     posts_data: &[liquid::model::Value],
+    templates: &permalink::Templates,
 ) -> Result<Vec<Paginator>> {
     let config = doc
         .front
@@ -33,11 +35,13 @@ pub(crate) fn generate_paginators(
     match config.include {
         Include::All => {
             sort_posts(&mut all_posts, config);
-            create_all_paginators(&all_posts, doc, config, None)
+            create_all_paginators(&all_posts, doc, config, None, templates)
+        }
+        Include::Tags => tags::create_tags_paginators(&all_posts, doc, config, templates),
+        Include::Categories => {
+            categories::create_categories_paginators(&all_posts, doc, config, templates)
         }
-        Include::Tags => tags::create_tags_paginators(&all_posts, doc, config),
-        Include::Categories => categories::create_categories_paginators(&all_posts, doc, config),
-        Include::Dates => dates::create_dates_paginators(&all_posts, doc, config),
+        Include::Dates => dates::create_dates_paginators(&all_posts, doc, config, templates),
         Include::None => {
             unreachable!("PaginationConfigBuilder should have lead to a None for pagination.")
         }
@@ -49,6 +53,7 @@ fn create_all_paginators(
     doc: &Document,
     pagination_cfg: &PaginationConfig,
     index_title: Option<&liquid::model::Value>,
+    templates: &permalink::Templates,
 ) -> Result<Vec<Paginator>> {
     let total_pages = all_posts.len();
     // f32 used here in order to not lose information to ceil the result,
@@ -58,15 +63,26 @@ fn create_all_paginators(
         .chunks(pagination_cfg.per_page as usize)
         .enumerate()
         .map(|(i, chunk)| {
-            paginator::create_paginator(
-                i,
-                total_indexes,
-                total_pages,
+            let index = i + 1;
+            let mut paginator = Paginator::new(total_indexes, total_pages);
+            paginator.set_first_last(doc, pagination_cfg, total_indexes, index_title, templates)?;
+            paginator.set_current_index_info(
+                index,
+                chunk,
                 pagination_cfg,
                 doc,
-                chunk,
                 index_title,
-            )
+                templates,
+            )?;
+            paginator.set_previous_next_info(
+                index,
+                total_indexes,
+                doc,
+                pagination_cfg,
+                index_title,
+                templates,
+            )?;
+            Ok(paginator)
         })
         .collect();
     paginators
@@ -141,9 +157,10 @@ fn interpret_permalink(
     doc: &Document,
     page_num: usize,
     index: Option<&liquid::model::Value>,
+    templates: &permalink::Templates,
 ) -> Result<String> {
     let mut attributes = document::permalink_attributes(&doc.front, &doc.file_path);
-    let permalink = permalink::explode_permalink(&config.front_permalink, &attributes)?;
+    let permalink = permalink::explode_permalink(&config.front_permalink, &attributes, templates)?;
     let permalink_path = std::path::Path::new(&permalink);
     let pagination_root = permalink_path
         .extension()
@@ -176,14 +193,14 @@ fn interpret_permalink(
             format!(
                 "{}/{}",
                 index,
-                permalink::explode_permalink(&config.permalink_suffix, &attributes)?
+                permalink::explode_permalink(&config.permalink_suffix, &attributes, templates)?
             )
         } else {
             format!(
                 "{}/{}/{}",
                 pagination_root,
                 index,
-                permalink::explode_permalink(&config.permalink_suffix, &attributes)?
+                permalink::explode_permalink(&config.permalink_suffix, &attributes, templates)?
             )
         }
     };
@@ -1,4 +1,6 @@
+use crate::cobalt_model::permalink;
 use crate::document::Document;
+use crate::term_feeds;
 
 use super::{PaginationConfig, Result, interpret_permalink};
 
@@ -17,6 +19,9 @@ pub(crate) struct Paginator {
     pub(crate) last_index_permalink: String,
     pub(crate) total_indexes: usize,
     pub(crate) total_pages: usize,
+    /// Feeds of the index's term, when it has any
+    pub(crate) rss: Option<String>,
+    pub(crate) jsonfeed: Option<String>,
 }
 
 impl Paginator {
@@ -35,18 +40,39 @@ impl Paginator {
             last_index_permalink: String::new(),
             total_indexes,
             total_pages,
+            rss: None,
+            jsonfeed: None,
         }
     }
 
+    /// Link the index to the feeds of its term, `term` being its path for nested categories
+    pub(crate) fn set_feeds(
+        &mut self,
+        config: &PaginationConfig,
+        variable: &str,
+        term: &[&str],
+        templates: &permalink::Templates,
+    ) -> Result<()> {
+        let feeds = &config.feeds;
+        self.rss = term_feeds::feed_path(feeds.rss.as_deref(), variable, term, templates)?
+            .map(|path| path.into_string());
+        self.jsonfeed =
+            term_feeds::feed_path(feeds.jsonfeed.as_deref(), variable, term, templates)?
+                .map(|path| path.into_string());
+        Ok(())
+    }
+
     pub(crate) fn set_first_last(
         &mut self,
         doc: &Document,
         config: &PaginationConfig,
         total_pages: usize,
         index_title: Option<&liquid::model::Value>,
+        templates: &permalink::Templates,
     ) -> Result<()> {
         self.first_index_permalink = doc.url_path.to_string();
-        self.last_index_permalink = interpret_permalink(config, doc, total_pages, index_title)?;
+        self.last_index_permalink =
+            interpret_permalink(config, doc, total_pages, index_title, templates)?;
         Ok(())
     }
 
@@ -57,11 +83,12 @@ impl Paginator {
         config: &PaginationConfig,
         doc: &Document,
         index_title: Option<&liquid::model::Value>,
+        templates: &permalink::Templates,
     ) -> Result<()> {
         self.index = index;
         self.pages = Some(all_pages.iter().map(|p| (*p).clone()).collect());
         self.index_title = index_title.cloned();
-        self.index_permalink = interpret_permalink(config, doc, index, index_title)?;
+        self.index_permalink = interpret_permalink(config, doc, index, index_title, templates)?;
         Ok(())
     }
 
@@ -72,43 +99,35 @@ impl Paginator {
         doc: &Document,
         config: &PaginationConfig,
         index_title: Option<&liquid::model::Value>,
+        templates: &permalink::Templates,
     ) -> Result<()> {
         if index > 1 {
             // we have a previous index
-            self.previous_index_permalink =
-                Some(interpret_permalink(config, doc, index - 1, index_title)?);
+            self.previous_index_permalink = Some(interpret_permalink(
+                config,
+                doc,
+                index - 1,
+                index_title,
+                templates,
+            )?);
             self.previous_index = index - 1;
         }
 
         if index < total_indexes {
             // we have a next index
             self.next_index = index + 1;
-            self.next_index_permalink =
-                Some(interpret_permalink(config, doc, index + 1, index_title)?);
+            self.next_index_permalink = Some(interpret_permalink(
+                config,
+                doc,
+                index + 1,
+                index_title,
+                templates,
+            )?);
         }
         Ok(())
     }
 }
 
-pub(crate) fn create_paginator(
-    i: usize,
-    total_indexes: usize,
-    total_pages: usize,
-    config: &PaginationConfig,
-    doc: &Document,
-    all_posts: &[&liquid::model::Value],
-    index_title: Option<&liquid::model::Value>,
-) -> Result<Paginator> {
-    let index = i + 1;
-    let mut paginator = Paginator::new(total_indexes, total_pages);
-
-    paginator.set_first_last(doc, config, total_indexes, index_title)?;
-    paginator.set_current_index_info(index, all_posts, config, doc, index_title)?;
-    paginator.set_previous_next_info(index, total_indexes, doc, config, index_title)?;
-
-    Ok(paginator)
-}
-
 #[allow(clippy::from_over_into)]
 impl Into<liquid::Object> for Paginator {
     fn into(self) -> liquid::Object {
@@ -179,6 +198,12 @@ impl Into<liquid::Object> for Paginator {
             "total_pages".into(),
             liquid::model::Value::scalar(self.total_pages as i32),
         );
+        if let Some(rss) = self.rss {
+            object.insert("rss".into(), liquid::model::Value::scalar(rss));
+        }
+        if let Some(jsonfeed) = self.jsonfeed {
+            object.insert("jsonfeed".into(), liquid::model::Value::scalar(jsonfeed));
+        }
         object
     }
 }
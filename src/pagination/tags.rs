@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 
 use crate::cobalt_model::pagination::PaginationConfig;
+use crate::cobalt_model::permalink;
 use crate::cobalt_model::slug;
 use crate::document::Document;
 
 use super::{Result, ValueView, create_all_paginators, helpers, paginator, sort_posts};
-use helpers::extract_tags;
+use helpers::{extract_list, extract_tags};
 use paginator::Paginator;
 
 #[allow(clippy::bind_instead_of_map)]
@@ -13,20 +14,23 @@ pub(crate) fn create_tags_paginators(
     all_posts: &[&liquid::model::Value],
     doc: &Document,
     pagination_cfg: &PaginationConfig,
+    templates: &permalink::Templates,
 ) -> Result<Vec<Paginator>> {
-    let mut per_tags = distribute_posts_by_tags(all_posts)?;
+    let mut per_tags = distribute_posts_by_tags(all_posts, pagination_cfg.field.as_deref())?;
 
     // create all other paginators
     let mut tag_paginators: TagPaginators = per_tags
         .iter_mut()
         .try_fold(TagPaginators::default(), |mut acc, (tag, posts)| {
             sort_posts(posts, pagination_cfg);
-            let cur_tag_paginators = create_all_paginators(
+            let mut cur_tag_paginators = create_all_paginators(
                 posts,
                 doc,
                 pagination_cfg,
                 Some(&liquid::model::Value::scalar(tag.to_owned())),
+                templates,
             )?;
+            cur_tag_paginators[0].set_feeds(pagination_cfg, "tag", &[tag], templates)?;
             acc.firsts_of_tags.push(cur_tag_paginators[0].clone());
             acc.paginators.extend(cur_tag_paginators.into_iter());
             Ok(acc)
@@ -48,10 +52,16 @@ pub(crate) fn create_tags_paginators(
 
 fn distribute_posts_by_tags<'a>(
     all_posts: &[&'a liquid::model::Value],
+    field: Option<&str>,
 ) -> Result<HashMap<String, Vec<&'a liquid::model::Value>>> {
     let mut per_tags: HashMap<String, Vec<&'a liquid::model::Value>> = HashMap::new();
     for post in all_posts {
-        if let Some(tags) = extract_tags(post.as_view()) {
+        // taxonomies can also group by lists under `data`, like `data.series`
+        let tags = match field {
+            Some(field) => extract_list(post.as_view(), field),
+            None => extract_tags(post.as_view()),
+        };
+        if let Some(tags) = tags {
             for tag in tags.values() {
                 let tag = tag
                     .as_scalar()
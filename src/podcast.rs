@@ -0,0 +1,248 @@
+use std::path;
+
+use rss::extension::itunes;
+
+use crate::error::Result;
+
+/// Fill in what `enclosure` leaves out: its length from the site's copy of the file and its MIME
+/// type from the file extension
+///
+/// `base_url` lets absolute URLs into the site be found locally too.
+pub(crate) fn resolve_enclosure(
+    enclosure: &mut cobalt_config::Enclosure,
+    root: &path::Path,
+    base_url: Option<&str>,
+) -> Result<()> {
+    if enclosure.length.is_none() {
+        let Some(rel_path) = site_path(&enclosure.url, base_url) else {
+            anyhow::bail!(
+                "Enclosure `{}` is not part of the site, it needs a `length`",
+                enclosure.url
+            );
+        };
+        let path = local_path(rel_path)
+            .ok_or_else(|| {
+                anyhow::format_err!("Enclosure `{}` is outside of the site", enclosure.url)
+            })?
+            .to_path(root);
+        let metadata = std::fs::metadata(&path).map_err(|err| {
+            anyhow::format_err!(
+                "Enclosure `{}` not found at `{}`: {}",
+                enclosure.url,
+                path.display(),
+                err
+            )
+        })?;
+        enclosure.length = Some(metadata.len());
+    }
+    if enclosure.mime_type.is_none() {
+        let mime_type = guess_mime_type(&enclosure.url).ok_or_else(|| {
+            anyhow::format_err!(
+                "Unknown media type of enclosure `{}`, it needs a `mime_type`",
+                enclosure.url
+            )
+        })?;
+        enclosure.mime_type = Some(liquid::model::KString::from_static(mime_type));
+    }
+    Ok(())
+}
+
+/// Where `url` is, relative to the site's root, when the site holds it
+fn site_path<'u>(url: &'u str, base_url: Option<&str>) -> Option<&'u str> {
+    let path = match url.strip_prefix('/') {
+        Some(path) => path,
+        None => url
+            .strip_prefix(base_url?.trim_end_matches('/'))?
+            .strip_prefix('/')?,
+    };
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    (!path.is_empty()).then_some(path)
+}
+
+/// The file at the URL path `path`, which can't leave the site's root
+fn local_path(path: &str) -> Option<relative_path::RelativePathBuf> {
+    let path = percent_encoding::percent_decode_str(path)
+        .decode_utf8()
+        .ok()?;
+    let escapes = path.split(['/', '\\']).any(|component| component == "..");
+    (!escapes).then(|| relative_path::RelativePathBuf::from(path.into_owned()))
+}
+
+fn guess_mime_type(url: &str) -> Option<&'static str> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let (_, ext) = path
+        .rsplit_once('/')
+        .unwrap_or(("", path))
+        .1
+        .rsplit_once('.')?;
+    let mime_type = match ext.to_ascii_lowercase().as_str() {
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/x-m4a",
+        "aac" => "audio/aac",
+        "ogg" | "oga" => "audio/ogg",
+        "opus" => "audio/opus",
+        "flac" => "audio/flac",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "m4v" => "video/x-m4v",
+        "mov" => "video/quicktime",
+        "webm" => "video/webm",
+        "pdf" => "application/pdf",
+        "epub" => "application/epub+zip",
+        _ => return None,
+    };
+    Some(mime_type)
+}
+
+/// Makes site-relative paths, like `/episodes/1.mp3`, absolute
+pub(crate) fn absolute_url(root_url: &str, url: &str) -> String {
+    if url.starts_with('/') {
+        format!("{}{}", root_url.trim_end_matches('/'), url)
+    } else {
+        url.to_owned()
+    }
+}
+
+pub(crate) fn to_rss_enclosure(
+    enclosure: &cobalt_config::Enclosure,
+    root_url: &str,
+) -> rss::Enclosure {
+    rss::EnclosureBuilder::default()
+        .url(absolute_url(root_url, &enclosure.url))
+        .length(enclosure.length.unwrap_or_default().to_string())
+        .mime_type(
+            enclosure
+                .mime_type
+                .as_deref()
+                .unwrap_or_default()
+                .to_owned(),
+        )
+        .build()
+}
+
+/// `itunes:` elements of an episode, if it has any
+pub(crate) fn item_extension(
+    enclosure: &cobalt_config::Enclosure,
+    root_url: &str,
+) -> Option<itunes::ITunesItemExtension> {
+    let cobalt_config::Enclosure {
+        duration,
+        explicit,
+        image,
+        episode,
+        season,
+        ..
+    } = enclosure;
+    if duration.is_none()
+        && explicit.is_none()
+        && image.is_none()
+        && episode.is_none()
+        && season.is_none()
+    {
+        return None;
+    }
+    let extension = itunes::ITunesItemExtensionBuilder::default()
+        .duration(duration.as_ref().map(|d| d.as_str().to_owned()))
+        .explicit(explicit.map(|explicit| explicit.to_string()))
+        .image(image.as_ref().map(|image| absolute_url(root_url, image)))
+        .episode(episode.map(|episode| episode.to_string()))
+        .season(season.map(|season| season.to_string()))
+        .build();
+    Some(extension)
+}
+
+/// `itunes:` elements of the show
+pub(crate) fn channel_extension(
+    podcast: &cobalt_config::Podcast,
+    root_url: &str,
+) -> itunes::ITunesChannelExtension {
+    let categories = podcast
+        .categories
+        .iter()
+        .map(|category| {
+            let mut levels = category.splitn(2, '/');
+            let text = levels.next().unwrap_or_default().to_owned();
+            let subcategory = levels.next().map(|sub| {
+                Box::new(
+                    itunes::ITunesCategoryBuilder::default()
+                        .text(sub.to_owned())
+                        .build(),
+                )
+            });
+            itunes::ITunesCategoryBuilder::default()
+                .text(text)
+                .subcategory(subcategory)
+                .build()
+        })
+        .collect::<Vec<_>>();
+    let owner = podcast.owner.as_ref().map(|owner| {
+        itunes::ITunesOwnerBuilder::default()
+            .name(owner.name.as_ref().map(|name| name.as_str().to_owned()))
+            .email(owner.email.as_ref().map(|email| email.as_str().to_owned()))
+            .build()
+    });
+    itunes::ITunesChannelExtensionBuilder::default()
+        .author(
+            podcast
+                .author
+                .as_ref()
+                .map(|author| author.as_str().to_owned()),
+        )
+        .image(
+            podcast
+                .image
+                .as_ref()
+                .map(|image| absolute_url(root_url, image)),
+        )
+        .categories(categories)
+        .explicit(Some(podcast.explicit.to_string()))
+        .owner(owner)
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn finds_site_paths() {
+        let base_url = Some("https://example.com/");
+        assert_eq!(site_path("/ep/1.mp3?dl=1", None), Some("ep/1.mp3"));
+        assert_eq!(
+            site_path("https://example.com/ep/1.mp3", base_url),
+            Some("ep/1.mp3")
+        );
+        assert_eq!(
+            site_path("https://cdn.example.com/ep/1.mp3", base_url),
+            None
+        );
+        assert_eq!(site_path("https://example.com.evil/1.mp3", base_url), None);
+    }
+
+    #[test]
+    fn decodes_local_paths() {
+        assert_eq!(
+            local_path("episodes/my%20show.mp3").unwrap().as_str(),
+            "episodes/my show.mp3"
+        );
+        assert_eq!(local_path("../x"), None);
+        assert_eq!(local_path("episodes/%2E%2E/%2e%2e/x"), None);
+    }
+
+    #[test]
+    fn rejects_enclosures_outside_of_the_site() {
+        let mut enclosure: cobalt_config::Enclosure = serde_yaml::from_str("url: /../x").unwrap();
+        let err = resolve_enclosure(&mut enclosure, path::Path::new("."), None).unwrap_err();
+        assert_eq!(err.to_string(), "Enclosure `/../x` is outside of the site");
+    }
+
+    #[test]
+    fn guesses_mime_types() {
+        assert_eq!(guess_mime_type("/ep/1.MP3"), Some("audio/mpeg"));
+        assert_eq!(
+            guess_mime_type("https://cdn.example.com/ep.v2/1.m4a#t=10"),
+            Some("audio/x-m4a")
+        );
+        assert_eq!(guess_mime_type("https://cdn.example.com/ep.v2/1"), None);
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt::Write as _;
+
+use crate::cobalt_model::permalink;
+use crate::document::Document;
+
+/// An old permalink of a document
+#[derive(Debug, Clone)]
+pub(crate) struct Redirect<'d> {
+    /// Old URL path, without the leading `/`
+    pub(crate) from: String,
+    pub(crate) to: &'d Document,
+}
+
+impl Redirect<'_> {
+    /// Where the redirect page is written, relative to the destination
+    pub(crate) fn file_path(&self) -> relative_path::RelativePathBuf {
+        permalink::format_url_as_file(&self.from)
+    }
+
+    /// Page sending browsers, and search engines, on to the document
+    pub(crate) fn to_html(&self, base_url: Option<&str>) -> String {
+        let url = escape(&format!(
+            "{}/{}",
+            base_url.unwrap_or_default(),
+            self.to.url_path
+        ));
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Redirecting…</title>
+<link rel="canonical" href="{url}">
+<meta name="robots" content="noindex">
+<meta http-equiv="refresh" content="0; url={url}">
+</head>
+<body>
+<p>This page has moved to <a href="{url}">{url}</a>.</p>
+</body>
+</html>
+"#
+        )
+    }
+}
+
+/// Every alias of `docs`, in document order
+pub(crate) fn collect<'d>(docs: impl IntoIterator<Item = &'d Document>) -> Vec<Redirect<'d>> {
+    docs.into_iter()
+        .flat_map(|doc| {
+            doc.front.aliases.iter().map(move |alias| Redirect {
+                from: alias.trim_start_matches('/').to_owned(),
+                to: doc,
+            })
+        })
+        .collect()
+}
+
+/// List of redirects for the server to apply itself
+pub(crate) fn redirects_file(
+    redirects: &[Redirect<'_>],
+    format: cobalt_config::RedirectsFormat,
+) -> crate::error::Result<String> {
+    let mut file = String::new();
+    for redirect in redirects {
+        let (from, to) = (&redirect.from, &redirect.to.url_path);
+        match format {
+            cobalt_config::RedirectsFormat::Netlify => writeln!(file, "/{from} /{to} 301")?,
+            cobalt_config::RedirectsFormat::Nginx => writeln!(file, "/{from} /{to};")?,
+        }
+    }
+    Ok(file)
+}
+
+fn escape(url: &str) -> String {
+    url.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn doc() -> Document {
+        let front: cobalt_config::Frontmatter =
+            serde_yaml::from_str("aliases: [/old.html, /older/]").unwrap();
+        let path = relative_path::RelativePathBuf::from("posts/new.md");
+        let front = front.merge_path(&path);
+        Document::new(
+            path.clone(),
+            &path,
+            front,
+            Default::default(),
+            &Default::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn alias_paths() {
+        let doc = doc();
+        let redirects = collect([&doc]);
+        let files: Vec<_> = redirects.iter().map(Redirect::file_path).collect();
+        assert_eq!(files, ["old.html", "older/index.html"]);
+    }
+
+    #[test]
+    fn page_links_to_document() {
+        let doc = doc();
+        let html = collect([&doc])[0].to_html(Some("https://example.com"));
+        assert!(
+            html.contains(r#"<link rel="canonical" href="https://example.com/posts/new.html">"#)
+        );
+        assert!(html.contains(r#"content="0; url=https://example.com/posts/new.html""#));
+    }
+
+    #[test]
+    fn file_formats() {
+        let doc = doc();
+        let redirects = collect([&doc]);
+        assert_eq!(
+            redirects_file(&redirects, cobalt_config::RedirectsFormat::Netlify).unwrap(),
+            "/old.html /posts/new.html 301\n/older/ /posts/new.html 301\n"
+        );
+        assert_eq!(
+            redirects_file(&redirects, cobalt_config::RedirectsFormat::Nginx).unwrap(),
+            "/old.html /posts/new.html;\n/older/ /posts/new.html;\n"
+        );
+    }
+}
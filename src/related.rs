@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use crate::pagination::extract_list;
+
+/// For each document, the other documents sharing the most weighted terms with it
+///
+/// Ties keep the collection's order.  Documents sharing nothing aren't related.
+pub(crate) fn rank_related(
+    docs: &[liquid::model::Value],
+    config: &cobalt_config::Related,
+) -> Vec<Vec<usize>> {
+    if config.limit == 0 || config.fields.is_empty() {
+        return vec![Vec::new(); docs.len()];
+    }
+
+    // documents carrying each term, so scoring only visits documents with something in common
+    let terms: Vec<Vec<(&str, String, u32)>> =
+        docs.iter()
+            .map(|doc| {
+                let mut doc_terms: Vec<_> = config
+                    .fields
+                    .iter()
+                    .filter_map(|(field, weight)| {
+                        let list = extract_list(doc.as_view(), field)?;
+                        Some(list.values().map(move |term| {
+                            (field.as_str(), term.to_kstr().into_string(), *weight)
+                        }))
+                    })
+                    .flatten()
+                    .collect();
+                // a term listed twice doesn't count twice
+                doc_terms.sort_unstable();
+                doc_terms.dedup();
+                doc_terms
+            })
+            .collect();
+    let mut carriers: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
+    for (i, doc_terms) in terms.iter().enumerate() {
+        for (field, term, _) in doc_terms {
+            carriers.entry((field, term.as_str())).or_default().push(i);
+        }
+    }
+
+    terms
+        .iter()
+        .enumerate()
+        .map(|(i, doc_terms)| {
+            let mut scores: HashMap<usize, u32> = HashMap::new();
+            for (field, term, weight) in doc_terms {
+                for &other in &carriers[&(*field, term.as_str())] {
+                    if other != i {
+                        *scores.entry(other).or_default() += weight;
+                    }
+                }
+            }
+            let mut related: Vec<_> = scores.into_iter().filter(|(_, score)| *score > 0).collect();
+            related
+                .sort_unstable_by(|(a, a_score), (b, b_score)| b_score.cmp(a_score).then(a.cmp(b)));
+            related.truncate(config.limit);
+            related.into_iter().map(|(other, _)| other).collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn doc(tags: &[&str], categories: &[&str]) -> liquid::model::Value {
+        let list = |terms: &[&str]| {
+            liquid::model::Value::Array(
+                terms
+                    .iter()
+                    .map(|t| liquid::model::Value::scalar((*t).to_owned()))
+                    .collect(),
+            )
+        };
+        liquid::model::Value::Object(liquid::object!({
+            "tags": list(tags),
+            "categories": list(categories),
+        }))
+    }
+
+    #[test]
+    fn ranks_by_shared_terms() {
+        let docs = [
+            doc(&["rust", "web"], &["Code"]),
+            doc(&["rust"], &[]),
+            doc(&["rust", "web"], &["Code"]),
+            doc(&["cooking"], &[]),
+        ];
+        let related = rank_related(&docs, &cobalt_config::Related::default());
+        assert_eq!(related, vec![vec![2, 1], vec![0, 2], vec![0, 1], vec![]]);
+    }
+
+    #[test]
+    fn weights_and_limit() {
+        let docs = [
+            doc(&["rust"], &["Code"]),
+            doc(&["rust"], &[]),
+            doc(&[], &["Code"]),
+        ];
+        let config: cobalt_config::Related =
+            serde_yaml::from_str("limit: 1\nfields: {tags: 1, categories: 3}").unwrap();
+        let related = rank_related(&docs, &config);
+        assert_eq!(related[0], vec![2]);
+    }
+
+    #[test]
+    fn disabled() {
+        let docs = [doc(&["rust"], &[]), doc(&["rust"], &[])];
+        let config: cobalt_config::Related = serde_yaml::from_str("limit: 0").unwrap();
+        assert!(rank_related(&docs, &config).iter().all(Vec::is_empty));
+    }
+}
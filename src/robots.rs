@@ -0,0 +1,86 @@
+use std::fmt::Write as _;
+
+use crate::error::Result;
+
+/// Render `robots.txt`, pointing crawlers at the sitemap when there is one
+///
+/// Builds including drafts are previews, so every crawler is kept out of them whatever the rules.
+pub(crate) fn robots_txt(
+    config: &cobalt_config::Robots,
+    sitemap_url: Option<&str>,
+    include_drafts: bool,
+) -> Result<String> {
+    if include_drafts {
+        return Ok("User-agent: *\nDisallow: /\n".to_owned());
+    }
+
+    let mut robots = String::new();
+    if config.rules.is_empty() {
+        robots.push_str("User-agent: *\nDisallow:\n");
+    }
+    for (i, rule) in config.rules.iter().enumerate() {
+        if i != 0 {
+            robots.push('\n');
+        }
+        writeln!(robots, "User-agent: {}", rule.user_agent)?;
+        for path in &rule.allow {
+            writeln!(robots, "Allow: {path}")?;
+        }
+        for path in &rule.disallow {
+            writeln!(robots, "Disallow: {path}")?;
+        }
+        if rule.allow.is_empty() && rule.disallow.is_empty() {
+            // a group needs a rule, an empty `Disallow` allowing everything
+            robots.push_str("Disallow:\n");
+        }
+    }
+    if let Some(sitemap_url) = sitemap_url {
+        write!(robots, "\nSitemap: {sitemap_url}\n")?;
+    }
+    Ok(robots)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn allows_everything_by_default() {
+        let config = cobalt_config::Robots::default();
+        assert_eq!(
+            robots_txt(&config, Some("http://example.com/sitemap.xml"), false).unwrap(),
+            "User-agent: *\nDisallow:\n\nSitemap: http://example.com/sitemap.xml\n"
+        );
+    }
+
+    #[test]
+    fn writes_rules() {
+        let config: cobalt_config::Robots = serde_yaml::from_str(
+            "rules:
+  - disallow: [/private/]
+    allow: [/private/shared.html]
+  - user_agent: BadBot
+    disallow: [/]",
+        )
+        .unwrap();
+        assert_eq!(
+            robots_txt(&config, None, false).unwrap(),
+            "User-agent: *
+Allow: /private/shared.html
+Disallow: /private/
+
+User-agent: BadBot
+Disallow: /
+"
+        );
+    }
+
+    #[test]
+    fn drafts_are_never_indexed() {
+        let config: cobalt_config::Robots = serde_yaml::from_str("rules:\n  - allow: [/]").unwrap();
+        assert_eq!(
+            robots_txt(&config, Some("http://example.com/sitemap.xml"), true).unwrap(),
+            "User-agent: *\nDisallow: /\n"
+        );
+    }
+}
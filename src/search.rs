@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+
+use liquid::ValueView;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::document::Document;
+use crate::error::Result;
+use crate::text::html_to_text;
+
+/// Version of elasticlunr whose serialized index format is produced
+const ELASTICLUNR_VERSION: &str = "0.9.5";
+
+/// Search index, gathered collection by collection as they are rendered
+#[derive(Debug)]
+pub(crate) struct IndexBuilder<'s> {
+    config: &'s cobalt_config::Search,
+    base_url: Option<&'s str>,
+    /// Entries of each collection, by its position in `search.collections`
+    collections: BTreeMap<usize, Vec<Map<String, Value>>>,
+}
+
+impl<'s> IndexBuilder<'s> {
+    pub(crate) fn new(config: &'s cobalt_config::Search, base_url: Option<&'s str>) -> Self {
+        Self {
+            config,
+            base_url,
+            collections: Default::default(),
+        }
+    }
+
+    /// Index the rendered `docs` of collection `slug`, if it is one being searched
+    pub(crate) fn add_collection(&mut self, slug: &str, docs: &[Document]) {
+        let Some(position) = self.config.collections.iter().position(|c| c == slug) else {
+            return;
+        };
+        let entries = docs.iter().map(|doc| self.entry(doc)).collect();
+        self.collections.insert(position, entries);
+    }
+
+    fn entry(&self, doc: &Document) -> Map<String, Value> {
+        self.config
+            .fields
+            .iter()
+            .filter_map(|field| {
+                let value = match field.as_str() {
+                    "permalink" => Value::String(format!(
+                        "{}/{}",
+                        self.base_url.unwrap_or_default(),
+                        doc.url_path
+                    )),
+                    "content" => {
+                        let text = attribute_str(doc, "content_text")?;
+                        Value::String(truncate(text, self.config.content_length))
+                    }
+                    "excerpt" => Value::String(html_to_text(&attribute_str(doc, "excerpt")?)),
+                    _ => {
+                        let value = doc.attributes.get(field.as_str()).or_else(|| {
+                            match doc.attributes.get("data")? {
+                                liquid::model::Value::Object(data) => data.get(field.as_str()),
+                                _ => None,
+                            }
+                        })?;
+                        serde_json::to_value(value).ok()?
+                    }
+                };
+                Some((field.to_string(), value))
+            })
+            .collect()
+    }
+
+    pub(crate) fn build(self) -> Result<String> {
+        let format = self.config.format;
+        let entries: Vec<_> = self.collections.into_values().flatten().collect();
+        let index = match format {
+            cobalt_config::SearchFormat::Documents => {
+                Value::Array(entries.into_iter().map(Value::Object).collect())
+            }
+            cobalt_config::SearchFormat::Elasticlunr => elasticlunr(&self.config.fields, entries),
+        };
+        Ok(serde_json::to_string(&index)?)
+    }
+}
+
+fn attribute_str(doc: &Document, name: &str) -> Option<String> {
+    let value = doc.attributes.get(name)?;
+    value.as_scalar().map(|s| s.to_kstr().into_string())
+}
+
+fn truncate(mut text: String, max_chars: usize) -> String {
+    if let Some((end, _)) = text.char_indices().nth(max_chars).filter(|_| 0 < max_chars) {
+        text.truncate(end);
+    }
+    text
+}
+
+/// Serialized elasticlunr index of `entries`, ready for `elasticlunr.Index.load`
+///
+/// Documents are referenced by their `id`, their URL.  Only the `trimmer` is applied to terms, so
+/// queries aren't stemmed or filtered of stop words either.
+fn elasticlunr(fields: &[liquid::model::KString], entries: Vec<Map<String, Value>>) -> Value {
+    let fields: Vec<&str> = fields
+        .iter()
+        .map(|field| field.as_str())
+        .filter(|field| *field != "permalink")
+        .collect();
+    let mut docs = Map::new();
+    let mut doc_info = Map::new();
+    let mut index: BTreeMap<&str, Map<String, Value>> =
+        fields.iter().map(|field| (*field, empty_node())).collect();
+    for (i, mut entry) in entries.into_iter().enumerate() {
+        let id = entry
+            .get("permalink")
+            .and_then(Value::as_str)
+            .map(str::to_owned)
+            .unwrap_or_else(|| i.to_string());
+        let mut info = Map::new();
+        for field in &fields {
+            let tokens = entry.get(*field).map(tokenize).unwrap_or_default();
+            info.insert((*field).to_owned(), tokens.len().into());
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for token in tokens {
+                *counts.entry(token).or_default() += 1;
+            }
+            let root = index.get_mut(field).expect("every field has a root");
+            for (token, count) in counts {
+                add_token(root, &token, &id, (count as f64).sqrt());
+            }
+        }
+        entry.insert("id".to_owned(), Value::String(id.clone()));
+        doc_info.insert(id.clone(), Value::Object(info));
+        docs.insert(id, Value::Object(entry));
+    }
+
+    serde_json::json!({
+        "version": ELASTICLUNR_VERSION,
+        "fields": fields,
+        "ref": "id",
+        "documentStore": {
+            "docs": docs,
+            "docInfo": doc_info,
+            "length": doc_info.len(),
+            "save": true,
+        },
+        "index": index
+            .into_iter()
+            .map(|(field, root)| (field.to_owned(), serde_json::json!({ "root": root })))
+            .collect::<Map<_, _>>(),
+        "pipeline": ["trimmer"],
+    })
+}
+
+/// Terms of a field the way elasticlunr's tokenizer and `trimmer` split them
+fn tokenize(value: &Value) -> Vec<String> {
+    let tokens: Vec<String> = match value {
+        Value::Array(values) => values
+            .iter()
+            .filter(|value| !value.is_null())
+            .map(|value| match value {
+                Value::String(s) => s.to_lowercase(),
+                value => value.to_string().to_lowercase(),
+            })
+            .collect(),
+        Value::Null => Vec::new(),
+        Value::String(s) => split(s),
+        value => split(&value.to_string()),
+    };
+    tokens
+        .into_iter()
+        .map(|token| {
+            // `\W` of JavaScript regexes, which is ASCII only
+            let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+            token
+                .trim_start_matches(|c| !is_word(c))
+                .trim_end_matches(|c| !is_word(c))
+                .to_owned()
+        })
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+fn split(text: &str) -> Vec<String> {
+    text.trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|token| !token.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+fn empty_node() -> Map<String, Value> {
+    let mut node = Map::new();
+    node.insert("docs".to_owned(), Value::Object(Map::new()));
+    node.insert("df".to_owned(), 0.into());
+    node
+}
+
+/// Record `token` in the character trie of a field, like `InvertedIndex.addToken`
+fn add_token(root: &mut Map<String, Value>, token: &str, id: &str, tf: f64) {
+    let mut node = root;
+    for c in token.chars() {
+        node = node
+            .entry(c.to_string())
+            .or_insert_with(|| Value::Object(empty_node()))
+            .as_object_mut()
+            .expect("trie nodes are objects");
+    }
+    let docs = node
+        .get_mut("docs")
+        .and_then(Value::as_object_mut)
+        .expect("trie nodes have docs");
+    let is_new = docs
+        .insert(id.to_owned(), serde_json::json!({ "tf": tf }))
+        .is_none();
+    if is_new {
+        let df = node["df"].as_u64().unwrap_or_default();
+        node.insert("df".to_owned(), (df + 1).into());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tokenize_like_elasticlunr() {
+        assert_eq!(
+            tokenize(&Value::String(" Hello, well-known World! ".to_owned())),
+            ["hello", "well", "known", "world"]
+        );
+        assert_eq!(
+            tokenize(&serde_json::json!(["Rust Lang", "Web"])),
+            ["rust lang", "web"]
+        );
+    }
+
+    #[test]
+    fn truncates_chars() {
+        assert_eq!(truncate("héllo".to_owned(), 2), "hé");
+        assert_eq!(truncate("héllo".to_owned(), 0), "héllo");
+    }
+
+    #[test]
+    fn elasticlunr_trie() {
+        let fields = ["title".into(), "permalink".into()];
+        let entry = serde_json::json!({ "title": "Go go", "permalink": "/go.html" });
+        let Value::Object(entry) = entry else {
+            unreachable!()
+        };
+        let index = elasticlunr(&fields, vec![entry]);
+        assert_eq!(index["fields"], serde_json::json!(["title"]));
+        assert_eq!(
+            index["documentStore"]["docInfo"]["/go.html"],
+            serde_json::json!({ "title": 2 })
+        );
+        let node = &index["index"]["title"]["root"]["g"]["o"];
+        assert_eq!(node["df"], 1);
+        assert_eq!(node["docs"]["/go.html"]["tf"], 2f64.sqrt());
+        assert_eq!(index["index"]["title"]["root"]["g"]["df"], 0);
+    }
+}
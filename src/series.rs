@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use liquid::model::Value;
+
+use crate::cobalt_model;
+use crate::document::Document;
+
+/// `page.series` for each document that is part of a series
+///
+/// Parts are ordered by `weight`, then `published_date`, then the collection's order.
+pub(crate) fn series_vars(docs: &[Document], docs_data: &[Value]) -> Vec<Option<Value>> {
+    let mut series: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, doc) in docs.iter().enumerate() {
+        if let Some(title) = doc.front.series.as_deref() {
+            series.entry(title).or_default().push(i);
+        }
+    }
+
+    // January 1, 1970 0:00:00 UTC, the beginning of time
+    let default_date = cobalt_model::DateTime::default();
+    let mut vars = vec![None; docs.len()];
+    for (title, mut parts) in series {
+        parts.sort_by_key(|&i| {
+            let front = &docs[i].front;
+            (front.weight, front.published_date.unwrap_or(default_date))
+        });
+        let parts_data: Vec<_> = parts.iter().map(|&i| docs_data[i].clone()).collect();
+        for (n, &i) in parts.iter().enumerate() {
+            let previous = n
+                .checked_sub(1)
+                .and_then(|previous| parts_data.get(previous))
+                .cloned()
+                .unwrap_or(Value::Nil);
+            let next = parts_data.get(n + 1).cloned().unwrap_or(Value::Nil);
+            let var: liquid::Object = [
+                ("title".into(), Value::scalar(title.to_owned())),
+                ("parts".into(), Value::Array(parts_data.clone())),
+                ("index".into(), Value::scalar((n + 1) as i64)),
+                ("previous_in_series".into(), previous),
+                ("next_in_series".into(), next),
+            ]
+            .into_iter()
+            .collect();
+            vars[i] = Some(Value::Object(var));
+        }
+    }
+    vars
+}
+
+#[cfg(test)]
+mod test {
+    use liquid::ValueView;
+
+    use super::*;
+
+    fn doc(name: &str, front: &str) -> Document {
+        let front: cobalt_config::Frontmatter = serde_yaml::from_str(front).unwrap();
+        let path = relative_path::RelativePathBuf::from(format!("posts/{name}.md"));
+        let front = front.merge_path(&path);
+        Document::new(
+            path.clone(),
+            &path,
+            front,
+            Default::default(),
+            &Default::default(),
+        )
+        .unwrap()
+    }
+
+    fn titles(var: &Value) -> Vec<String> {
+        let Value::Object(var) = var else {
+            panic!("series should be an object");
+        };
+        let Some(Value::Array(parts)) = var.get("parts") else {
+            panic!("parts should be an array");
+        };
+        parts
+            .iter()
+            .map(|part| match part {
+                Value::Object(part) => part["title"].to_kstr().into_string(),
+                _ => panic!("parts should be documents"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn orders_parts() {
+        let docs = [
+            doc(
+                "c",
+                "series: Intro\npublished_date: 2016-01-03 00:00:00 +0000",
+            ),
+            doc(
+                "b",
+                "series: Intro\npublished_date: 2016-01-02 00:00:00 +0000",
+            ),
+            doc("other", "published_date: 2016-01-02 00:00:00 +0000"),
+            doc(
+                "a",
+                "series: Intro\npublished_date: 2016-01-04 00:00:00 +0000\nweight: -1",
+            ),
+        ];
+        let data: Vec<_> = docs
+            .iter()
+            .map(|doc| Value::Object(doc.attributes.clone()))
+            .collect();
+        let vars = series_vars(&docs, &data);
+        assert!(vars[2].is_none());
+        let var = vars[1].as_ref().unwrap();
+        assert_eq!(titles(var), ["A", "B", "C"]);
+        let Value::Object(var) = var else {
+            unreachable!()
+        };
+        assert_eq!(var["index"], Value::scalar(2));
+        assert_eq!(var["title"], Value::scalar("Intro"));
+    }
+}
@@ -0,0 +1,222 @@
+use std::io::Write;
+
+use xml::writer::{EmitterConfig, EventWriter, XmlEvent};
+
+use crate::cobalt_model::DateTime;
+use crate::error::Result;
+
+const NAMESPACE: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// Most URLs the protocol allows in one `<urlset>`
+pub(crate) const MAX_URLS: usize = 50_000;
+
+/// A page listed in the sitemap
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Entry {
+    pub(crate) loc: String,
+    pub(crate) lastmod: Option<DateTime>,
+    pub(crate) changefreq: Option<cobalt_config::ChangeFreq>,
+    pub(crate) priority: Option<cobalt_config::SitemapPriority>,
+    /// The page in every language it is available in, as `(lang, url)`
+    pub(crate) alternates: Vec<(String, String)>,
+}
+
+/// Render a `<urlset>` listing `entries`
+pub(crate) fn urlset(entries: &[Entry]) -> Result<String> {
+    let mut buff = Vec::new();
+    let mut writer = EmitterConfig::new()
+        .perform_indent(true)
+        .create_writer(&mut buff);
+    let mut urlset = XmlEvent::start_element("urlset").default_ns(NAMESPACE);
+    if entries.iter().any(|entry| !entry.alternates.is_empty()) {
+        urlset = urlset.ns("xhtml", XHTML_NAMESPACE);
+    }
+    writer.write(urlset)?;
+    for entry in entries {
+        writer.write(XmlEvent::start_element("url"))?;
+        write_content(&mut writer, "loc", &normalize_url(&entry.loc))?;
+        if let Some(lastmod) = entry.lastmod {
+            write_lastmod(&mut writer, lastmod)?;
+        }
+        if let Some(changefreq) = entry.changefreq {
+            write_content(&mut writer, "changefreq", changefreq.as_str())?;
+        }
+        if let Some(priority) = entry.priority {
+            write_content(&mut writer, "priority", &priority.to_string())?;
+        }
+        for (lang, href) in &entry.alternates {
+            let href = normalize_url(href);
+            writer.write(
+                XmlEvent::start_element("xhtml:link")
+                    .attr("rel", "alternate")
+                    .attr("hreflang", lang)
+                    .attr("href", &href),
+            )?;
+            writer.write(XmlEvent::end_element())?;
+        }
+        writer.write(XmlEvent::end_element())?;
+    }
+    writer.write(XmlEvent::end_element())?;
+    Ok(String::from_utf8(buff)?)
+}
+
+/// Render a `<sitemapindex>` pointing at each urlset, as `(loc, lastmod)`
+pub(crate) fn sitemapindex(sitemaps: &[(String, Option<DateTime>)]) -> Result<String> {
+    let mut buff = Vec::new();
+    let mut writer = EmitterConfig::new()
+        .perform_indent(true)
+        .create_writer(&mut buff);
+    writer.write(XmlEvent::start_element("sitemapindex").default_ns(NAMESPACE))?;
+    for (loc, lastmod) in sitemaps {
+        writer.write(XmlEvent::start_element("sitemap"))?;
+        write_content(&mut writer, "loc", &normalize_url(loc))?;
+        if let Some(lastmod) = lastmod {
+            write_lastmod(&mut writer, *lastmod)?;
+        }
+        writer.write(XmlEvent::end_element())?;
+    }
+    writer.write(XmlEvent::end_element())?;
+    Ok(String::from_utf8(buff)?)
+}
+
+/// Where the urlsets of a sitemap of `count` URLs go when it is too big for one, its index taking
+/// `path`
+///
+/// `sitemap.xml` is split into `sitemap-1.xml`, `sitemap-2.xml`, etc.
+pub(crate) fn urlset_paths(
+    path: &relative_path::RelativePath,
+    count: usize,
+) -> Vec<relative_path::RelativePathBuf> {
+    if count <= MAX_URLS {
+        return Vec::new();
+    }
+    let stem = path.file_stem().unwrap_or("sitemap");
+    let ext = path
+        .extension()
+        .map(|ext| format!(".{ext}"))
+        .unwrap_or_default();
+    (1..=count.div_ceil(MAX_URLS))
+        .map(|i| path.with_file_name(format!("{stem}-{i}{ext}")))
+        .collect()
+}
+
+fn write_lastmod<W: Write>(writer: &mut EventWriter<W>, lastmod: DateTime) -> Result<()> {
+    let lastmod = chrono::DateTime::parse_from_rfc2822(&lastmod.to_rfc2822())
+        .expect("chrono/time compatible RFC 2822 implementations");
+    write_content(writer, "lastmod", &lastmod.to_rfc3339())
+}
+
+fn write_content<W: Write>(writer: &mut EventWriter<W>, name: &str, content: &str) -> Result<()> {
+    writer.write(XmlEvent::start_element(name))?;
+    writer.write(XmlEvent::characters(content))?;
+    writer.write(XmlEvent::end_element())?;
+    Ok(())
+}
+
+/// Percent-encode characters that aren't allowed in a URL, like spaces
+fn normalize_url(url: &str) -> String {
+    url::Url::parse(url)
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| url.to_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn urlset_plain() {
+        let entries = [Entry {
+            loc: "http://example.com/my post.html".to_owned(),
+            ..Default::default()
+        }];
+        assert_eq!(
+            urlset(&entries).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>http://example.com/my%20post.html</loc>
+  </url>
+</urlset>"#
+        );
+    }
+
+    #[test]
+    fn urlset_alternates() {
+        let entries = [Entry {
+            loc: "http://example.com/a.html".to_owned(),
+            alternates: vec![
+                ("de".to_owned(), "http://example.com/de/a.html".to_owned()),
+                ("en".to_owned(), "http://example.com/a.html".to_owned()),
+            ],
+            ..Default::default()
+        }];
+        assert_eq!(
+            urlset(&entries).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:xhtml="http://www.w3.org/1999/xhtml">
+  <url>
+    <loc>http://example.com/a.html</loc>
+    <xhtml:link rel="alternate" hreflang="de" href="http://example.com/de/a.html" />
+    <xhtml:link rel="alternate" hreflang="en" href="http://example.com/a.html" />
+  </url>
+</urlset>"#
+        );
+    }
+
+    #[test]
+    fn urlset_hints() {
+        let entries = [Entry {
+            loc: "http://example.com/a.html".to_owned(),
+            changefreq: Some(cobalt_config::ChangeFreq::Weekly),
+            priority: Some(0.8.try_into().unwrap()),
+            ..Default::default()
+        }];
+        assert_eq!(
+            urlset(&entries).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>http://example.com/a.html</loc>
+    <changefreq>weekly</changefreq>
+    <priority>0.8</priority>
+  </url>
+</urlset>"#
+        );
+    }
+
+    #[test]
+    fn splits_big_sitemaps() {
+        let path = relative_path::RelativePath::new("maps/sitemap.xml");
+        assert!(urlset_paths(path, MAX_URLS).is_empty());
+        let paths = urlset_paths(path, 2 * MAX_URLS + 1);
+        let paths: Vec<_> = paths.iter().map(|path| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "maps/sitemap-1.xml",
+                "maps/sitemap-2.xml",
+                "maps/sitemap-3.xml"
+            ]
+        );
+    }
+
+    #[test]
+    fn index_lists_urlsets() {
+        let sitemaps = [(
+            "http://example.com/sitemap-1.xml".to_owned(),
+            DateTime::from_str("2024-01-01 10:00:00 +0000"),
+        )];
+        assert_eq!(
+            sitemapindex(&sitemaps).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap>
+    <loc>http://example.com/sitemap-1.xml</loc>
+    <lastmod>2024-01-01T10:00:00+00:00</lastmod>
+  </sitemap>
+</sitemapindex>"#
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use crate::cobalt_model::Collection;
+use crate::cobalt_model::permalink;
+use crate::cobalt_model::slug;
+use crate::document::Document;
+use crate::error::Result;
+
+/// Feed of the documents under one tag or category
+#[derive(Debug)]
+pub(crate) struct TermFeed<'d> {
+    /// Tag, or `/`-separated category path, as written in the frontmatter
+    pub(crate) term: String,
+    pub(crate) rss: Option<relative_path::RelativePathBuf>,
+    pub(crate) jsonfeed: Option<relative_path::RelativePathBuf>,
+    pub(crate) documents: Vec<&'d Document>,
+}
+
+/// Feeds of every tag and category of `collection`, in term order
+pub(crate) fn term_feeds<'d>(
+    collection: &Collection,
+    documents: &'d [Document],
+    templates: &permalink::Templates,
+) -> Result<Vec<TermFeed<'d>>> {
+    let mut feeds = Vec::new();
+    if has_feeds(&collection.tag_feeds) {
+        let mut per_tag: BTreeMap<&str, Vec<&Document>> = BTreeMap::new();
+        for doc in documents {
+            for tag in &doc.front.tags {
+                per_tag.entry(tag.as_str()).or_default().push(doc);
+            }
+        }
+        for (tag, documents) in per_tag {
+            feeds.push(TermFeed {
+                term: tag.to_owned(),
+                rss: feed_path(
+                    collection.tag_feeds.rss.as_deref(),
+                    "tag",
+                    &[tag],
+                    templates,
+                )?,
+                jsonfeed: feed_path(
+                    collection.tag_feeds.jsonfeed.as_deref(),
+                    "tag",
+                    &[tag],
+                    templates,
+                )?,
+                documents,
+            });
+        }
+    }
+    if has_feeds(&collection.category_feeds) {
+        let mut per_category: BTreeMap<Vec<&str>, Vec<&Document>> = BTreeMap::new();
+        for doc in documents {
+            let categories: Vec<_> = doc.front.categories.iter().map(|c| c.as_str()).collect();
+            // documents belong to every parent of their category too
+            for depth in 1..=categories.len() {
+                per_category
+                    .entry(categories[..depth].to_vec())
+                    .or_default()
+                    .push(doc);
+            }
+        }
+        let feeds_config = &collection.category_feeds;
+        for (category, documents) in per_category {
+            feeds.push(TermFeed {
+                term: category.join("/"),
+                rss: feed_path(
+                    feeds_config.rss.as_deref(),
+                    "category",
+                    &category,
+                    templates,
+                )?,
+                jsonfeed: feed_path(
+                    feeds_config.jsonfeed.as_deref(),
+                    "category",
+                    &category,
+                    templates,
+                )?,
+                documents,
+            });
+        }
+    }
+    Ok(feeds)
+}
+
+fn has_feeds(config: &cobalt_config::TermFeeds) -> bool {
+    config.rss.is_some() || config.jsonfeed.is_some()
+}
+
+/// Where the feed of a term goes, `variable` in `template` being the slug of the term
+///
+/// Categories are nested, their slug being that of each level joined by `/`.
+pub(crate) fn feed_path(
+    template: Option<&str>,
+    variable: &str,
+    term: &[&str],
+    templates: &permalink::Templates,
+) -> Result<Option<relative_path::RelativePathBuf>> {
+    let Some(template) = template else {
+        return Ok(None);
+    };
+    let term_slug = itertools::join(term.iter().map(|level| slug::slugify(*level)), "/");
+    let attributes: liquid::Object = [(
+        liquid::model::KString::from_ref(variable),
+        liquid::model::Value::scalar(term_slug),
+    )]
+    .into_iter()
+    .collect();
+    let path = permalink::explode_permalink(template, &attributes, templates).map_err(|err| {
+        anyhow::format_err!("Failed to create feed path from `{}`: {}", template, err)
+    })?;
+    Ok(Some(relative_path::RelativePathBuf::from(path)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn slugs_terms() {
+        let templates = permalink::Templates::default();
+        let path = feed_path(
+            Some("tags/{{ tag }}/feed.xml"),
+            "tag",
+            &["Rust Lang"],
+            &templates,
+        )
+        .unwrap();
+        assert_eq!(path.unwrap().as_str(), "tags/rust-lang/feed.xml");
+        let path = feed_path(
+            Some("/{{ category }}.json"),
+            "category",
+            &["Code", "C++"],
+            &templates,
+        )
+        .unwrap();
+        assert_eq!(path.unwrap().as_str(), "code/c.json");
+        assert_eq!(feed_path(None, "tag", &["rust"], &templates).unwrap(), None);
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Text of an HTML fragment as a reader sees it, with whitespace collapsed
+pub(crate) fn html_to_text(html: &str) -> String {
+    static HIDDEN: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?is)<!--.*?-->|<script\b.*?</script\s*>|<style\b.*?</style\s*>").unwrap()
+    });
+    static TAG: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"</?([a-zA-Z][a-zA-Z0-9]*)\b[^>]*>").unwrap());
+
+    let html = HIDDEN.replace_all(html, " ");
+    let text = TAG.replace_all(&html, |captures: &regex::Captures<'_>| {
+        // inline elements are part of the surrounding word, like `<em>un</em>likely`
+        let name = captures[1].to_ascii_lowercase();
+        if INLINE.contains(&name.as_str()) {
+            ""
+        } else {
+            " "
+        }
+    });
+    let text = decode_entities(&text);
+    itertools::join(text.split_whitespace(), " ")
+}
+
+/// Number of words in plain `text`
+///
+/// Chinese and Japanese don't separate words with spaces, so each of their characters counts as a
+/// word, as is usual for reading speeds.  Runs of punctuation alone aren't words.
+pub(crate) fn word_count(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_cjk(c) {
+            count += 1;
+            in_word = false;
+        } else if c.is_whitespace() {
+            in_word = false;
+        } else if c.is_alphanumeric() && !in_word {
+            count += 1;
+            in_word = true;
+        }
+    }
+    count
+}
+
+/// Minutes needed to read `words`, rounded up
+pub(crate) fn reading_time(words: usize, words_per_minute: u32) -> usize {
+    words.div_ceil(words_per_minute.max(1) as usize)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Unified Ideographs Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{FF66}'..='\u{FF9D}' // Halfwidth Katakana
+        | '\u{20000}'..='\u{2FA1F}' // CJK Unified Ideographs Extension B and beyond
+    )
+}
+
+const INLINE: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "cite", "code", "data", "dfn", "em", "i", "kbd", "mark", "q",
+    "s", "samp", "small", "span", "strong", "sub", "sup", "time", "u", "var",
+];
+
+fn decode_entities(text: &str) -> String {
+    static ENTITY: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap());
+    ENTITY
+        .replace_all(text, |captures: &regex::Captures<'_>| {
+            let entity = &captures[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(|code| char::from_u32(code.ok()?)),
+            };
+            decoded
+                .map(String::from)
+                .unwrap_or_else(|| captures[0].to_owned())
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strips_markup() {
+        let html = "<h1 id=\"title\">Hello</h1>\n<p>An <em>un</em>likely <a href=\"/\">link</a>.</p><p>Next</p>";
+        assert_eq!(html_to_text(html), "Hello An unlikely link. Next");
+    }
+
+    #[test]
+    fn drops_scripts_and_comments() {
+        let html = "<p>Before</p><script>let x = '<p>';</script><!-- <p>hidden</p> --><style>p {}</style>After";
+        assert_eq!(html_to_text(html), "Before After");
+    }
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(
+            html_to_text("Fish &amp; chips &lt;3 &#8212; &#x263A; &unknown;"),
+            "Fish & chips <3 \u{2014} \u{263A} &unknown;"
+        );
+    }
+
+    #[test]
+    fn counts_words() {
+        assert_eq!(word_count("Hello, well-known world — again!"), 4);
+        assert_eq!(word_count("don't   stop"), 2);
+        assert_eq!(word_count(""), 0);
+    }
+
+    #[test]
+    fn counts_cjk_characters() {
+        assert_eq!(word_count("你好，世界。"), 4);
+        assert_eq!(word_count("Rustは楽しい"), 5);
+        assert_eq!(word_count("안녕하세요 세계"), 2);
+    }
+
+    #[test]
+    fn rounds_reading_time_up() {
+        assert_eq!(reading_time(0, 200), 0);
+        assert_eq!(reading_time(1, 200), 1);
+        assert_eq!(reading_time(400, 200), 2);
+        assert_eq!(reading_time(401, 200), 3);
+    }
+}